@@ -1,8 +1,475 @@
 use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, Attribute, Data, DeriveInput, Error, Fields, GenericArgument, Ident, LitStr,
+    PathArguments, Result, Type,
+};
 
-#[proc_macro_derive(Builder)]
+#[proc_macro_derive(Builder, attributes(builder))]
 pub fn derive(input: TokenStream) -> TokenStream {
-    let _ = input;
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// How a field of the input struct is stored in the builder and populated by
+/// `build`.
+enum Kind<'a> {
+    /// Plain `T`: stored as `Option<T>` and required by `build`.
+    Required(&'a Type),
+    /// `Option<T>`: stored as `Option<T>` and allowed to stay unset.
+    Optional(&'a Type),
+    /// `#[builder(each = "...")] Vec<T>`: stored as `Vec<T>`, starts empty.
+    Repeated { each: Ident, elem: &'a Type },
+}
+
+struct Field<'a> {
+    ident: &'a Ident,
+    ty: &'a Type,
+    kind: Kind<'a>,
+}
+
+/// Options given on the struct itself, as in `#[builder(build_fn(clone))]`.
+#[derive(Default)]
+struct Options {
+    /// Generate `build(&self)` which clones the stored values, instead of
+    /// `build(&mut self)` which moves them out of the builder.
+    clone_build: bool,
+    /// Also generate a `<Name>Patch` type for partial updates of built values.
+    patch: bool,
+    /// Also generate `reset` and `reset_<field>` methods which put fields back
+    /// into their initial unset state.
+    reset: bool,
+    /// Also generate `<field>_with` methods which edit a field in place.
+    with_setters: bool,
+}
+
+/// The generated type that setters and resetters are being emitted for.
+#[derive(Clone, Copy, PartialEq)]
+enum Target {
+    Builder,
+    /// Every field is stored as `Option<FieldType>`, where `None` means leave
+    /// the field of the patched value untouched.
+    Patch,
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2> {
+    let options = parse_options(&input.attrs)?;
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "Builder can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "Builder can only be derived for structs",
+            ))
+        }
+    };
+
+    let fields = fields
+        .iter()
+        .map(|field| {
+            let ident = field.ident.as_ref().unwrap();
+            let ty = &field.ty;
+            let kind = match parse_each(field)? {
+                Some(each) => match unwrap_generic(ty, "Vec") {
+                    Some(elem) => Kind::Repeated { each, elem },
+                    None => {
+                        return Err(Error::new_spanned(
+                            ty,
+                            "`each` requires a field of type Vec<T>",
+                        ))
+                    }
+                },
+                None => match unwrap_generic(ty, "Option") {
+                    Some(inner) => Kind::Optional(inner),
+                    None => Kind::Required(ty),
+                },
+            };
+            Ok(Field { ident, ty, kind })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let vis = &input.vis;
+    let ident = &input.ident;
+    let builder = format_ident!("{}Builder", ident);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let storage = fields.iter().map(|field| {
+        let ident = field.ident;
+        let ty = match field.kind {
+            Kind::Required(ty) | Kind::Optional(ty) => quote!(::std::option::Option<#ty>),
+            Kind::Repeated { .. } => {
+                let ty = field.ty;
+                quote!(#ty)
+            }
+        };
+        quote!(#ident: #ty)
+    });
+
+    let init = fields.iter().map(|field| {
+        let ident = field.ident;
+        match field.kind {
+            Kind::Required(_) | Kind::Optional(_) => quote!(#ident: ::std::option::Option::None),
+            Kind::Repeated { .. } => quote!(#ident: ::std::vec::Vec::new()),
+        }
+    });
+
+    let setters = fields.iter().map(|field| setter(field, Target::Builder));
+    let with_setters = fields
+        .iter()
+        .filter(|_| options.with_setters)
+        .map(with_setter);
+
+    let resetters = if options.reset {
+        let resetters = fields.iter().map(|field| resetter(field, Target::Builder));
+        let reset_fields = fields
+            .iter()
+            .map(|field| format_ident!("reset_{}", field.ident));
+        Some(quote! {
+            #(#resetters)*
+
+            #vis fn reset(&mut self) -> &mut Self {
+                #(self.#reset_fields();)*
+                self
+            }
+        })
+    } else {
+        None
+    };
+
+    // Every required field is checked before any value is moved out of the
+    // builder, so that a failed build leaves the builder as it was.
+    let build_checks = fields.iter().filter_map(|field| match field.kind {
+        Kind::Required(_) => {
+            let ident = field.ident;
+            let msg = format!("field `{}` is not set", ident);
+            Some(quote! {
+                if self.#ident.is_none() {
+                    return ::std::result::Result::Err(
+                        ::std::boxed::Box::<dyn ::std::error::Error>::from(#msg),
+                    );
+                }
+            })
+        }
+        Kind::Optional(_) | Kind::Repeated { .. } => None,
+    });
+    let build_fields = fields.iter().map(|field| {
+        let ident = field.ident;
+        let value = match (&field.kind, options.clone_build) {
+            (Kind::Required(_), false) | (Kind::Optional(_), false) => quote!(self.#ident.take()),
+            (Kind::Repeated { .. }, false) => quote!(::std::mem::take(&mut self.#ident)),
+            (_, true) => quote!(::std::clone::Clone::clone(&self.#ident)),
+        };
+        match field.kind {
+            Kind::Required(_) => quote!(#ident: ::std::option::Option::unwrap(#value)),
+            Kind::Optional(_) | Kind::Repeated { .. } => quote!(#ident: #value),
+        }
+    });
+    let build_receiver = if options.clone_build {
+        quote!(&self)
+    } else {
+        quote!(&mut self)
+    };
+
+    let patch = if options.patch {
+        Some(expand_patch(&input, &fields, &options))
+    } else {
+        None
+    };
+
+    Ok(quote! {
+        #vis struct #builder #impl_generics #where_clause {
+            #(#storage,)*
+        }
+
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #vis fn builder() -> #builder #ty_generics {
+                #builder {
+                    #(#init,)*
+                }
+            }
+        }
+
+        impl #impl_generics #builder #ty_generics #where_clause {
+            #(#setters)*
+
+            #(#with_setters)*
+
+            #resetters
+
+            #vis fn build(
+                #build_receiver,
+            ) -> ::std::result::Result<
+                #ident #ty_generics,
+                ::std::boxed::Box<dyn ::std::error::Error>,
+            > {
+                #(#build_checks)*
+                ::std::result::Result::Ok(#ident {
+                    #(#build_fields,)*
+                })
+            }
+        }
+
+        #patch
+    })
+}
+
+fn expand_patch(input: &DeriveInput, fields: &[Field], options: &Options) -> TokenStream2 {
+    let vis = &input.vis;
+    let ident = &input.ident;
+    let patch = format_ident!("{}Patch", ident);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let storage = fields.iter().map(|field| {
+        let ident = field.ident;
+        let ty = field.ty;
+        quote!(#vis #ident: ::std::option::Option<#ty>)
+    });
+    let field_idents = fields.iter().map(|field| field.ident).collect::<Vec<_>>();
+    let field_tys = fields.iter().map(|field| field.ty);
+
+    let setters = fields.iter().map(|field| setter(field, Target::Patch));
+    let resetters = fields
+        .iter()
+        .filter(|_| options.reset)
+        .map(|field| resetter(field, Target::Patch));
+
+    quote! {
+        #vis struct #patch #impl_generics #where_clause {
+            #(#storage,)*
+        }
+
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #vis fn patch() -> #patch #ty_generics {
+                #patch {
+                    #(#field_idents: ::std::option::Option::None,)*
+                }
+            }
+        }
+
+        impl #impl_generics #patch #ty_generics #where_clause {
+            #(#setters)*
+
+            #(#resetters)*
+
+            #vis fn apply(self, target: &mut #ident #ty_generics) {
+                #(
+                    if let ::std::option::Option::Some(value) = self.#field_idents {
+                        target.#field_idents = value;
+                    }
+                )*
+            }
+
+            #vis fn diff(old: &#ident #ty_generics, new: &#ident #ty_generics) -> Self
+            where
+                #(for<'a> #field_tys: ::std::cmp::PartialEq + ::std::clone::Clone,)*
+            {
+                #patch {
+                    #(
+                        #field_idents: if ::std::cmp::PartialEq::ne(
+                            &old.#field_idents,
+                            &new.#field_idents,
+                        ) {
+                            ::std::option::Option::Some(::std::clone::Clone::clone(
+                                &new.#field_idents,
+                            ))
+                        } else {
+                            ::std::option::Option::None
+                        },
+                    )*
+                }
+            }
+        }
+    }
+}
+
+fn setter(field: &Field, target: Target) -> TokenStream2 {
+    let ident = field.ident;
+    match &field.kind {
+        Kind::Required(ty) | Kind::Optional(ty) => {
+            let mut value = quote!(::std::option::Option::Some(#ident));
+            if let (Kind::Optional(_), Target::Patch) = (&field.kind, target) {
+                value = quote!(::std::option::Option::Some(#value));
+            }
+            quote! {
+                pub fn #ident(&mut self, #ident: #ty) -> &mut Self {
+                    self.#ident = #value;
+                    self
+                }
+            }
+        }
+        Kind::Repeated { each, elem } => {
+            let ty = field.ty;
+            // A patch replaces the whole Vec of the patched value, so it only
+            // gets the all-at-once setter; pushing a single element onto an
+            // empty Vec would look like appending but drop the existing ones.
+            if target == Target::Patch {
+                return quote! {
+                    pub fn #ident(&mut self, #ident: #ty) -> &mut Self {
+                        self.#ident = ::std::option::Option::Some(#ident);
+                        self
+                    }
+                };
+            }
+            let one_at_a_time = quote! {
+                pub fn #each(&mut self, #each: #elem) -> &mut Self {
+                    self.#ident.push(#each);
+                    self
+                }
+            };
+            // The all-at-once setter would collide with the one-at-a-time
+            // setter if they share a name.
+            if each == ident {
+                return one_at_a_time;
+            }
+            quote! {
+                #one_at_a_time
+
+                pub fn #ident(&mut self, #ident: #ty) -> &mut Self {
+                    self.#ident = #ident;
+                    self
+                }
+            }
+        }
+    }
+}
+
+fn with_setter(field: &Field) -> TokenStream2 {
+    let ident = field.ident;
+    let method = format_ident!("{}_with", ident);
+    match &field.kind {
+        // The `for<'a>` keeps the bound from being checked eagerly, so fields
+        // whose type is not Default still compile; the method is just not
+        // callable for them.
+        Kind::Required(ty) | Kind::Optional(ty) => quote! {
+            pub fn #method<F>(&mut self, f: F) -> &mut Self
+            where
+                F: ::std::ops::FnOnce(&mut #ty),
+                for<'a> #ty: ::std::default::Default,
+            {
+                f(self.#ident.get_or_insert_with(::std::default::Default::default));
+                self
+            }
+        },
+        Kind::Repeated { .. } => {
+            let ty = field.ty;
+            quote! {
+                pub fn #method<F>(&mut self, f: F) -> &mut Self
+                where
+                    F: ::std::ops::FnOnce(&mut #ty),
+                {
+                    f(&mut self.#ident);
+                    self
+                }
+            }
+        }
+    }
+}
+
+fn resetter(field: &Field, target: Target) -> TokenStream2 {
+    let ident = field.ident;
+    let method = format_ident!("reset_{}", ident);
+    let empty = match (&field.kind, target) {
+        (Kind::Repeated { .. }, Target::Builder) => quote!(::std::vec::Vec::new()),
+        _ => quote!(::std::option::Option::None),
+    };
+    quote! {
+        pub fn #method(&mut self) -> &mut Self {
+            self.#ident = #empty;
+            self
+        }
+    }
+}
+
+fn parse_options(attrs: &[Attribute]) -> Result<Options> {
+    let mut options = Options::default();
+    for attr in attrs {
+        if !attr.path().is_ident("builder") {
+            continue;
+        }
+        let expected = || {
+            Error::new_spanned(
+                &attr.meta,
+                "expected `builder(build_fn(clone))`, `builder(patch)`, `builder(reset)` or `builder(with_setters)`",
+            )
+        };
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("patch") {
+                options.patch = true;
+                return Ok(());
+            }
+            if meta.path.is_ident("reset") {
+                options.reset = true;
+                return Ok(());
+            }
+            if meta.path.is_ident("with_setters") {
+                options.with_setters = true;
+                return Ok(());
+            }
+            if !meta.path.is_ident("build_fn") {
+                return Err(expected());
+            }
+            meta.parse_nested_meta(|meta| {
+                if meta.path.is_ident("clone") {
+                    options.clone_build = true;
+                    Ok(())
+                } else {
+                    Err(expected())
+                }
+            })
+        })?;
+    }
+    Ok(options)
+}
+
+fn parse_each(field: &syn::Field) -> Result<Option<Ident>> {
+    let mut each = None;
+    for attr in &field.attrs {
+        if !attr.path().is_ident("builder") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("each") {
+                let lit: LitStr = meta.value()?.parse()?;
+                each = Some(lit.parse()?);
+                Ok(())
+            } else {
+                Err(Error::new_spanned(
+                    &attr.meta,
+                    "expected `builder(each = \"...\")`",
+                ))
+            }
+        })?;
+    }
+    Ok(each)
+}
 
-    unimplemented!()
+/// Returns `T` if `ty` is spelled `Wrapper<T>`, for example `Option<T>`.
+fn unwrap_generic<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let path = match ty {
+        Type::Path(ty) if ty.qself.is_none() => &ty.path,
+        _ => return None,
+    };
+    let segment = path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    let args = match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => &args.args,
+        _ => return None,
+    };
+    match &args[0] {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    }
 }
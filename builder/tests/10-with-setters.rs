@@ -0,0 +1,57 @@
+// With #[builder(with_setters)] on the struct, generate a `<field>_with`
+// method for every field that hands the caller a mutable reference to the
+// value being built, initializing it first from `Default::default()` (or as an
+// empty Vec for fields with `each`) if nothing has been set yet.
+//
+// This covers edits that the plain and `each` setters cannot express, such as
+// removing or reordering elements that were already added. Like the reset
+// methods, these are opt-in so that fields `x` and `x_with` do not collide.
+//
+//     impl CommandBuilder {
+//         pub fn env_with<F>(&mut self, f: F) -> &mut Self
+//         where
+//             F: FnOnce(&mut Vec<String>),
+//         {
+//             f(&mut self.env);
+//             self
+//         }
+//     }
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(with_setters)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    env: Vec<String>,
+    current_dir: Option<String>,
+    timeout: Timeout,
+}
+
+// Not Default, so `timeout_with` exists but cannot be called.
+pub struct Timeout(u64);
+
+fn main() {
+    let command = Command::builder()
+        .executable_with(|e| e.push_str("cargo"))
+        .arg("build".to_owned())
+        .arg("--verbose".to_owned())
+        .arg("--release".to_owned())
+        .args_with(|args| args.retain(|arg| arg != "--verbose"))
+        .args_with(|args| args.reverse())
+        .env_with(|env| env.push("RUST_LOG=debug".to_owned()))
+        .env_with(|env| env.push("RUST_BACKTRACE=1".to_owned()))
+        .env_with(|env| env.retain(|var| var.starts_with("RUST_LOG")))
+        .current_dir_with(|dir| dir.push_str(".."))
+        .timeout(Timeout(30))
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["--release", "build"]);
+    assert_eq!(command.env, vec!["RUST_LOG=debug"]);
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+    assert_eq!(command.timeout.0, 30);
+}
@@ -0,0 +1,39 @@
+// With #[builder(build_fn(clone))] on the struct, `build` takes `&self` and
+// clones the stored values instead of moving them out of the builder. One
+// configured builder can then be used as a template for many values.
+//
+//     impl CommandBuilder {
+//         pub fn build(&self) -> Result<Command, Box<dyn Error>> {
+//             ...
+//         }
+//     }
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(build_fn(clone))]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+}
+
+fn main() {
+    let mut template = Command::builder();
+    template
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .current_dir("..".to_owned());
+
+    let template = &template;
+    let commands = (0..3)
+        .map(|_| template.build().unwrap())
+        .collect::<Vec<_>>();
+
+    for command in &commands {
+        assert_eq!(command.executable, "cargo");
+        assert_eq!(command.args, vec!["build"]);
+        assert_eq!(command.current_dir.as_deref(), Some(".."));
+    }
+}
@@ -0,0 +1,70 @@
+// With #[builder(reset)] on the struct, generate `reset_<field>` methods which
+// put a single field back into its initial unset state, and a `reset` method
+// which does the same for every field. For an Option field this clears it back
+// to None, and for a field with `each` it empties the Vec.
+//
+// These are opt-in so that structs with a field named `reset`, or with fields
+// `x` and `reset_x`, keep working without the attribute. The same goes for
+// fields `x` and `x_with` without #[builder(with_setters)].
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(build_fn(clone), reset)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+}
+
+// Without the attribute there are no reset methods to collide with.
+#[derive(Builder)]
+pub struct Job {
+    reset: bool,
+    timeout: u32,
+    reset_timeout: u32,
+    delay: u32,
+    delay_with: u32,
+}
+
+#[allow(dead_code)]
+fn job() {
+    let _ = Job::builder()
+        .reset(true)
+        .timeout(1)
+        .reset_timeout(2)
+        .delay(3)
+        .delay_with(4)
+        .build();
+}
+
+fn main() {
+    let mut builder = Command::builder();
+    builder
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .current_dir("..".to_owned());
+
+    builder.reset_args().reset_current_dir();
+    let command = builder.build().unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert!(command.args.is_empty());
+    assert!(command.current_dir.is_none());
+
+    builder.reset_executable();
+    assert!(builder.build().is_err());
+
+    builder
+        .executable("rustc".to_owned())
+        .arg("--version".to_owned())
+        .current_dir("/".to_owned())
+        .reset();
+    assert!(builder.build().is_err());
+
+    let command = builder.executable("rustc".to_owned()).build().unwrap();
+    assert_eq!(command.executable, "rustc");
+    assert!(command.args.is_empty());
+    assert!(command.current_dir.is_none());
+}
+
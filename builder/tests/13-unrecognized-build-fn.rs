@@ -0,0 +1,11 @@
+// Options on the struct are validated the same way as the field attributes.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(build_fn(clon))]
+pub struct Command {
+    executable: String,
+}
+
+fn main() {}
@@ -0,0 +1,61 @@
+// With #[builder(patch)] on the struct, also generate a `CommandPatch` type in
+// which every field is optional, for partial updates of a value that has
+// already been built. The patch has the same setters as the builder, and
+// `apply` overwrites only the fields that were set on it.
+//
+// A field with `each` only gets the setter taking the whole Vec on the patch,
+// since applying the patch replaces the Vec rather than appending to it. With
+// #[builder(reset)] the patch gets `reset_<field>` methods too.
+//
+//     pub struct CommandPatch {
+//         pub executable: Option<String>,
+//         pub args: Option<Vec<String>>,
+//         pub current_dir: Option<Option<String>>,
+//     }
+//
+// When every field type is PartialEq and Clone, `CommandPatch::diff(&old,
+// &new)` produces the patch that turns `old` into `new`.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Clone, PartialEq, Debug)]
+#[builder(patch, reset)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+}
+
+fn main() {
+    let mut command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .build()
+        .unwrap();
+
+    let mut patch = Command::patch();
+    patch.args(vec!["test".to_owned(), "--release".to_owned()]);
+    patch.current_dir("..".to_owned());
+    patch.apply(&mut command);
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["test", "--release"]);
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+
+    let mut patch = Command::patch();
+    patch.executable("rustc".to_owned()).reset_executable();
+    patch.apply(&mut command);
+    assert_eq!(command.executable, "cargo");
+
+    let old = command.clone();
+    let mut new = command.clone();
+    new.executable = "rustc".to_owned();
+    new.current_dir = None;
+    let patch = CommandPatch::diff(&old, &new);
+    assert_eq!(patch.executable.as_deref(), Some("rustc"));
+    assert!(patch.args.is_none());
+    assert_eq!(patch.current_dir, Some(None));
+
+    patch.apply(&mut command);
+    assert_eq!(command, new);
+}
@@ -0,0 +1,32 @@
+// A failed call to `build` should not lose any of the values which were set
+// on the builder. Check that every required field is set before moving any of
+// them out, so that the caller can set the missing field and try again.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: String,
+    env: Option<Vec<String>>,
+}
+
+fn main() {
+    let mut builder = Command::builder();
+    builder
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .env(vec![]);
+
+    let err = builder.build().err().unwrap();
+    assert_eq!(err.to_string(), "field `current_dir` is not set");
+
+    builder.current_dir("..".to_owned());
+    let command = builder.build().unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build"]);
+    assert_eq!(command.current_dir, "..");
+    assert_eq!(command.env, Some(vec![]));
+}
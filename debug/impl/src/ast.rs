@@ -0,0 +1,206 @@
+use crate::{attr, fmt};
+use proc_macro2::Span;
+use syn::ext::IdentExt;
+use syn::punctuated::Punctuated;
+use syn::{
+    Attribute, Data, DataEnum, DataStruct, DeriveInput, Error, Fields, Generics, Ident, Index,
+    Member, Meta, Result, Token, Type,
+};
+
+pub enum Input<'a> {
+    Struct(Struct<'a>),
+    Enum(Enum<'a>),
+}
+
+pub struct Struct<'a> {
+    pub attrs: attr::Container,
+    pub ident: &'a Ident,
+    pub generics: &'a Generics,
+    pub style: Style,
+    pub fields: Vec<Field<'a>>,
+}
+
+pub struct Enum<'a> {
+    pub attrs: attr::Container,
+    pub ident: &'a Ident,
+    pub generics: &'a Generics,
+    pub variants: Vec<Variant<'a>>,
+    /// The integer type from `#[repr(...)]`, if any.
+    pub repr: Option<Ident>,
+}
+
+pub struct Variant<'a> {
+    pub attrs: attr::Variant,
+    pub ident: &'a Ident,
+    pub style: Style,
+    pub fields: Vec<Field<'a>>,
+}
+
+pub struct Field<'a> {
+    pub attrs: attr::Field,
+    pub member: Member,
+    pub ty: &'a Type,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum Style {
+    Named,
+    Tuple,
+    Unit,
+}
+
+impl<'a> Field<'a> {
+    /// The field's name as printed: the identifier without any `r#`, or the
+    /// index of a tuple field.
+    pub fn name(&self) -> String {
+        match &self.member {
+            Member::Named(ident) => ident.unraw().to_string(),
+            Member::Unnamed(index) => index.index.to_string(),
+        }
+    }
+
+    /// The name printed in the output, after `#[debug(rename = "...")]`.
+    pub fn printed_name(&self) -> String {
+        match &self.attrs.rename {
+            Some(rename) => rename.value(),
+            None => self.name(),
+        }
+    }
+}
+
+impl<'a> Input<'a> {
+    pub fn from_syn(node: &'a DeriveInput) -> Result<Self> {
+        match &node.data {
+            Data::Struct(data) => Struct::from_syn(node, data).map(Input::Struct),
+            Data::Enum(data) => Enum::from_syn(node, data).map(Input::Enum),
+            Data::Union(_) => Err(Error::new_spanned(
+                &node.ident,
+                "CustomDebug does not support unions",
+            )),
+        }
+    }
+}
+
+impl<'a> Struct<'a> {
+    fn from_syn(node: &'a DeriveInput, data: &'a DataStruct) -> Result<Self> {
+        let (style, fields) = fields_from_syn(&data.fields)?;
+        let attrs = attr::container(&node.attrs)?;
+        if attrs.discriminant.is_some() {
+            return Err(Error::new_spanned(
+                &node.ident,
+                "`debug(discriminant)` is only supported on enums",
+            ));
+        }
+        Ok(Struct {
+            attrs,
+            ident: &node.ident,
+            generics: &node.generics,
+            style,
+            fields,
+        })
+    }
+}
+
+impl<'a> Enum<'a> {
+    fn from_syn(node: &'a DeriveInput, data: &'a DataEnum) -> Result<Self> {
+        let variants: Vec<Variant> = data
+            .variants
+            .iter()
+            .map(|variant| {
+                let (style, fields) = fields_from_syn(&variant.fields)?;
+                Ok(Variant {
+                    attrs: attr::variant(&variant.attrs)?,
+                    ident: &variant.ident,
+                    style,
+                    fields,
+                })
+            })
+            .collect::<Result<_>>()?;
+        let attrs = attr::container(&node.attrs)?;
+        if attrs.name.is_some() || attrs.type_params {
+            return Err(Error::new_spanned(
+                &node.ident,
+                "`debug(name)` and `debug(type_params)` are only supported on structs",
+            ));
+        }
+        if attrs.discriminant.is_some() {
+            if let Some(variant) = variants.iter().find(|variant| variant.style != Style::Unit) {
+                return Err(Error::new_spanned(
+                    variant.ident,
+                    "`debug(discriminant)` requires an enum with only unit variants",
+                ));
+            }
+        }
+        if attrs.logfmt {
+            return Err(Error::new_spanned(
+                &node.ident,
+                "`debug(logfmt)` is only supported on structs",
+            ));
+        }
+        Ok(Enum {
+            attrs,
+            ident: &node.ident,
+            generics: &node.generics,
+            variants,
+            repr: repr(&node.attrs)?,
+        })
+    }
+}
+
+fn repr(attrs: &[Attribute]) -> Result<Option<Ident>> {
+    const INTS: [&str; 12] = [
+        "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+    ];
+    let mut repr = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("repr")) {
+        let metas = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+        for meta in metas {
+            if let Meta::Path(path) = meta {
+                if let Some(ident) = path.get_ident() {
+                    if INTS.iter().any(|int| ident == int) {
+                        repr = Some(ident.clone());
+                    }
+                }
+            }
+        }
+    }
+    Ok(repr)
+}
+
+fn fields_from_syn(fields: &Fields) -> Result<(Style, Vec<Field<'_>>)> {
+    let style = match fields {
+        Fields::Named(_) => Style::Named,
+        Fields::Unnamed(_) => Style::Tuple,
+        Fields::Unit => Style::Unit,
+    };
+    let fields = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let attrs = attr::field(&field.attrs)?;
+            if attrs.flatten && style != Style::Named {
+                return Err(Error::new_spanned(
+                    field,
+                    "`debug(flatten)` is only supported on named fields",
+                ));
+            }
+            let member = match &field.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(Index {
+                    index: i as u32,
+                    span: Span::call_site(),
+                }),
+            };
+            let field = Field {
+                attrs,
+                member,
+                ty: &field.ty,
+            };
+            for format in field.attrs.format.iter().chain(&field.attrs.alternate) {
+                fmt::check_single(format, &field.name())?;
+            }
+            Ok(field)
+        })
+        .collect::<Result<_>>()?;
+    Ok((style, fields))
+}
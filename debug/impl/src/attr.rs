@@ -0,0 +1,365 @@
+use crate::fmt;
+use proc_macro2::Span;
+use quote::ToTokens;
+use syn::meta::ParseNestedMeta;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{
+    Attribute, Error, Expr, ExprLit, ExprPath, Lit, LitInt, LitStr, Meta, Path, Result, Token,
+    WherePredicate,
+};
+
+/// Attributes on the struct or enum itself.
+#[derive(Default)]
+pub struct Container {
+    /// `#[debug(bound = "...")]`: replaces every inferred bound.
+    pub bound: Option<Vec<WherePredicate>>,
+    /// `#[debug(fmt = "...", args...)]`: replaces the whole output.
+    pub fmt: Option<Fmt>,
+    /// `#[debug(name = "...")]`: printed in place of the struct's identifier.
+    pub name: Option<LitStr>,
+    /// `#[debug(type_params)]`: append the concrete generic arguments to the
+    /// printed name, as in `Field<u8>`.
+    pub type_params: bool,
+    /// `#[debug(phantom = "...")]`: wrapper types which, like `PhantomData`,
+    /// print without needing their type arguments to implement `Debug`.
+    pub phantom: Vec<Path>,
+    /// `#[debug(max_depth = N)]`: print `Name { .. }` once N values with a
+    /// depth limit are already being printed further up the stack.
+    pub max_depth: Option<usize>,
+    /// `#[debug(logfmt)]`: also generate `fmt_kv`, printing the fields as
+    /// logfmt key-value pairs.
+    pub logfmt: bool,
+    /// `#[debug(discriminant)]` or `#[debug(discriminant(radix = N))]` on a
+    /// C-like enum: print each variant's discriminant after its name, in the
+    /// given radix.
+    pub discriminant: Option<u32>,
+}
+
+/// Attributes on an enum variant.
+#[derive(Default)]
+pub struct Variant {
+    /// `#[debug(bound = "...")]`: replaces the bounds inferred from the fields
+    /// of this variant.
+    pub bound: Option<Vec<WherePredicate>>,
+}
+
+/// Attributes on a field.
+#[derive(Default)]
+pub struct Field {
+    /// `#[debug = "..."]`: format string applied to the field's value.
+    pub format: Option<LitStr>,
+    /// `#[debug(bound = "...")]`: replaces the bounds inferred from the type of
+    /// this field.
+    pub bound: Option<Vec<WherePredicate>>,
+    /// `#[debug(alternate = "...")]`: format string applied to the field's
+    /// value in place of the usual output under `{:#?}`.
+    pub alternate: Option<LitStr>,
+    /// `#[debug(rename = "...")]`: printed in place of the field's name.
+    pub rename: Option<LitStr>,
+    /// `#[debug(skip)]`: leave the field out and end the output with `..`.
+    pub skip: bool,
+    /// `#[debug(flatten)]`: print the fields of this field's value as if they
+    /// were fields of the parent struct.
+    pub flatten: bool,
+    /// `#[debug(skip_if = "path")]`: leave the field out whenever the
+    /// predicate returns true for its value.
+    pub skip_if: Option<ExprPath>,
+    /// `#[debug(redact)]` or `#[debug(redact(len))]`: print a placeholder in
+    /// place of the value.
+    pub redact: Option<Redact>,
+    /// `#[debug(with = "path")]`: function with the signature of
+    /// `Debug::fmt` which prints the field in place of its `Debug` impl.
+    pub with: Option<ExprPath>,
+    /// `#[debug(hex)]` or `#[debug(bytes)]` on a byte buffer.
+    pub bytes: Option<ByteFormat>,
+    /// `#[debug(units = "...")]` on a number.
+    pub units: Option<Units>,
+    /// `#[debug(limit = N)]`: print at most N elements of a collection, or N
+    /// characters of a string.
+    pub limit: Option<usize>,
+    /// `#[debug(fmt = "...", args...)]`: format string with arbitrary
+    /// arguments, evaluated with `self` in scope, printed as the value.
+    pub fmt: Option<Fmt>,
+}
+
+/// A format string followed by its arguments, as in `format_args!`.
+pub struct Fmt {
+    pub lit: LitStr,
+    pub args: Vec<Expr>,
+}
+
+pub enum ByteFormat {
+    /// `0102ff`, or a dump like `xxd` under `{:#?}`.
+    Hex,
+    /// `b"\x01\x02\xff"`
+    Literal,
+}
+
+pub enum Units {
+    /// `units = "bytes"`: `1.5 MiB`
+    Bytes,
+    /// `units = "duration_ms"`: `2.3s`
+    DurationMs,
+    /// `units = "percent"`: `42%`
+    Percent,
+}
+
+pub enum Redact {
+    /// `<redacted>`
+    Placeholder,
+    /// `<redacted len=N>`, for strings and collections.
+    Len,
+}
+
+impl Field {
+    /// Whether the value is printed through its `Debug` impl, and so needs a
+    /// `Debug` bound if it mentions a type parameter.
+    pub fn uses_debug(&self) -> bool {
+        self.format.is_none()
+            && !self.skip
+            && self.redact.is_none()
+            && self.with.is_none()
+            && self.fmt.is_none()
+            && self.bytes.is_none()
+            && self.units.is_none()
+    }
+}
+
+pub fn container(attrs: &[Attribute]) -> Result<Container> {
+    let mut container = Container::default();
+    for attr in debug_attrs(attrs) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("bound") {
+                container.bound = Some(parse_bound(&meta.value()?.parse()?)?);
+                Ok(())
+            } else if meta.path.is_ident("fmt") {
+                container.fmt = Some(parse_fmt(&meta)?);
+                Ok(())
+            } else if meta.path.is_ident("name") {
+                container.name = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("type_params") {
+                container.type_params = true;
+                Ok(())
+            } else if meta.path.is_ident("discriminant") {
+                container.discriminant = Some(10);
+                if meta.input.is_empty() || meta.input.peek(Token![,]) {
+                    return Ok(());
+                }
+                meta.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("radix") {
+                        let lit: LitInt = meta.value()?.parse()?;
+                        let radix = lit.base10_parse()?;
+                        if ![2, 8, 10, 16].contains(&radix) {
+                            return Err(Error::new(lit.span(), "radix must be 2, 8, 10 or 16"));
+                        }
+                        container.discriminant = Some(radix);
+                        Ok(())
+                    } else {
+                        Err(meta.error("expected `discriminant(radix = N)`"))
+                    }
+                })
+            } else if meta.path.is_ident("logfmt") {
+                container.logfmt = true;
+                Ok(())
+            } else if meta.path.is_ident("max_depth") {
+                let lit: LitInt = meta.value()?.parse()?;
+                container.max_depth = Some(lit.base10_parse()?);
+                Ok(())
+            } else if meta.path.is_ident("phantom") {
+                let lit: LitStr = meta.value()?.parse()?;
+                let paths = lit.parse_with(Punctuated::<Path, Token![,]>::parse_terminated)?;
+                container.phantom.extend(paths);
+                Ok(())
+            } else {
+                Err(unsupported(&meta))
+            }
+        })?;
+    }
+    Ok(container)
+}
+
+pub fn variant(attrs: &[Attribute]) -> Result<Variant> {
+    let mut variant = Variant::default();
+    for attr in debug_attrs(attrs) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("bound") {
+                variant.bound = Some(parse_bound(&meta.value()?.parse()?)?);
+                Ok(())
+            } else {
+                Err(unsupported(&meta))
+            }
+        })?;
+    }
+    Ok(variant)
+}
+
+pub fn field(attrs: &[Attribute]) -> Result<Field> {
+    let mut field = Field::default();
+    let mut renderer = None;
+    for attr in debug_attrs(attrs) {
+        match &attr.meta {
+            Meta::NameValue(meta) => match &meta.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(lit), ..
+                }) => {
+                    set_renderer(&mut renderer, "debug = \"...\"", attr.span())?;
+                    field.format = Some(lit.clone());
+                }
+                value => return Err(Error::new_spanned(value, "expected `debug = \"...\"`")),
+            },
+            Meta::List(_) => attr.parse_nested_meta(|meta| {
+                let mut set_renderer = |name| set_renderer(&mut renderer, name, meta.path.span());
+                if meta.path.is_ident("bound") {
+                    field.bound = Some(parse_bound(&meta.value()?.parse()?)?);
+                    Ok(())
+                } else if meta.path.is_ident("skip") {
+                    field.skip = true;
+                    Ok(())
+                } else if meta.path.is_ident("hex") {
+                    set_renderer("debug(hex)")?;
+                    field.bytes = Some(ByteFormat::Hex);
+                    Ok(())
+                } else if meta.path.is_ident("bytes") {
+                    set_renderer("debug(bytes)")?;
+                    field.bytes = Some(ByteFormat::Literal);
+                    Ok(())
+                } else if meta.path.is_ident("units") {
+                    set_renderer("debug(units)")?;
+                    let lit: LitStr = meta.value()?.parse()?;
+                    field.units =
+                        Some(match lit.value().as_str() {
+                            "bytes" => Units::Bytes,
+                            "duration_ms" => Units::DurationMs,
+                            "percent" => Units::Percent,
+                            _ => return Err(Error::new(
+                                lit.span(),
+                                "expected `units = \"bytes\"`, `\"duration_ms\"` or `\"percent\"`",
+                            )),
+                        });
+                    Ok(())
+                } else if meta.path.is_ident("limit") {
+                    set_renderer("debug(limit)")?;
+                    let lit: LitInt = meta.value()?.parse()?;
+                    field.limit = Some(lit.base10_parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("flatten") {
+                    // The flattened value adds its own fields, so nothing else
+                    // gets to render it.
+                    set_renderer("debug(flatten)")?;
+                    field.flatten = true;
+                    Ok(())
+                } else if meta.path.is_ident("skip_if") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    field.skip_if = Some(lit.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("alternate") {
+                    field.alternate = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("rename") {
+                    field.rename = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("fmt") {
+                    set_renderer("debug(fmt)")?;
+                    field.fmt = Some(parse_fmt(&meta)?);
+                    Ok(())
+                } else if meta.path.is_ident("with") {
+                    set_renderer("debug(with)")?;
+                    let lit: LitStr = meta.value()?.parse()?;
+                    field.with = Some(lit.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("redact") {
+                    set_renderer("debug(redact)")?;
+                    field.redact = Some(Redact::Placeholder);
+                    if meta.input.is_empty() || meta.input.peek(Token![,]) {
+                        return Ok(());
+                    }
+                    meta.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("len") {
+                            field.redact = Some(Redact::Len);
+                            Ok(())
+                        } else {
+                            Err(meta.error("expected `redact(len)`"))
+                        }
+                    })
+                } else {
+                    Err(unsupported(&meta))
+                }
+            })?,
+            Meta::Path(meta) => return Err(Error::new_spanned(meta, "expected `debug = \"...\"`")),
+        }
+    }
+    if let Some(alternate) = &field.alternate {
+        // Under `{:#?}` the alternate format would print the value that
+        // `skip` or `redact` is there to hide.
+        let hidden_by = if field.skip {
+            Some("skip")
+        } else if field.redact.is_some() {
+            Some("redact")
+        } else {
+            None
+        };
+        if let Some(hidden_by) = hidden_by {
+            let msg = format!(
+                "`debug(alternate)` cannot be combined with `debug({})`",
+                hidden_by,
+            );
+            return Err(Error::new(alternate.span(), msg));
+        }
+    }
+    Ok(field)
+}
+
+/// Records that the field's value is printed by the attribute `name`, and
+/// rejects a second attribute which would print it some other way.
+fn set_renderer(renderer: &mut Option<&'static str>, name: &'static str, span: Span) -> Result<()> {
+    let msg = match renderer.replace(name) {
+        None => return Ok(()),
+        Some(previous) if previous == name => format!("duplicate `{}`", name),
+        Some(previous) => format!("`{}` cannot be combined with `{}`", name, previous),
+    };
+    Err(Error::new(span, msg))
+}
+
+/// The format string of `#[display("...")]`, for derive(CustomDisplay).
+pub fn display(attrs: &[Attribute]) -> Result<Option<LitStr>> {
+    let mut display = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("display")) {
+        if display.is_some() {
+            return Err(Error::new_spanned(attr, "duplicate `display` attribute"));
+        }
+        display = Some(attr.parse_args()?);
+    }
+    Ok(display)
+}
+
+fn debug_attrs(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs.iter().filter(|attr| attr.path().is_ident("debug"))
+}
+
+fn unsupported(meta: &ParseNestedMeta) -> Error {
+    let path = meta.path.to_token_stream().to_string().replace(' ', "");
+    meta.error(format_args!("unsupported debug attribute `{}`", path))
+}
+
+/// Parses `fmt = "...", args...`, where every expression up to the end of
+/// the attribute is taken as an argument of the format string.
+fn parse_fmt(meta: &ParseNestedMeta) -> Result<Fmt> {
+    let lit: LitStr = meta.value()?.parse()?;
+    let mut args = Vec::new();
+    while !meta.input.is_empty() {
+        meta.input.parse::<Token![,]>()?;
+        if meta.input.is_empty() {
+            break;
+        }
+        args.push(meta.input.parse()?);
+    }
+    fmt::check(&lit, &args)?;
+    Ok(Fmt { lit, args })
+}
+
+fn parse_bound(lit: &LitStr) -> Result<Vec<WherePredicate>> {
+    let predicates = lit.parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)?;
+    Ok(predicates.into_iter().collect())
+}
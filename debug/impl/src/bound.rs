@@ -0,0 +1,197 @@
+use crate::ast::Field;
+use quote::ToTokens;
+use std::collections::BTreeSet;
+use syn::visit::{self, Visit};
+use syn::{
+    parse_quote, GenericParam, Generics, Ident, Path, Type, TypePath, TypeTraitObject,
+    WherePredicate,
+};
+
+/// Where-clause predicates accumulated from the fields of the input, without
+/// duplicates.
+pub struct Bounds<'a> {
+    params: BTreeSet<&'a Ident>,
+    phantom: &'a [Path],
+    seen: BTreeSet<String>,
+    predicates: Vec<WherePredicate>,
+}
+
+impl<'a> Bounds<'a> {
+    /// Types named in `phantom` are treated like `PhantomData` by `infer`.
+    pub fn new(generics: &'a Generics, phantom: &'a [Path]) -> Self {
+        let params = generics
+            .params
+            .iter()
+            .filter_map(|param| match param {
+                GenericParam::Type(param) => Some(&param.ident),
+                _ => None,
+            })
+            .collect();
+        Bounds {
+            params,
+            phantom,
+            seen: BTreeSet::new(),
+            predicates: Vec::new(),
+        }
+    }
+
+    /// Infers a `Debug` bound for every type parameter, and every associated
+    /// type of a type parameter, that the fields print through `Debug`.
+    ///
+    /// Type parameters which only appear inside of `PhantomData` get no bound,
+    /// and neither do fields which are not printed with `Debug`, such as those
+    /// with a custom format string. Fields with `units` need `Number` on top of
+    /// `Debug`, and fields with `limit` need their whole type to implement
+    /// `DebugLimited` on top of it. Fields with `hex` or `bytes` need their
+    /// whole type to implement `AsRef<[u8]>` instead, and flattened fields
+    /// `DebugFields` and `DebugDiff`. A field with its own `bound` attribute
+    /// contributes exactly those predicates instead.
+    pub fn infer(&mut self, fields: &[Field]) {
+        let mut explicit = Vec::new();
+        for field in fields {
+            if let Some(bound) = &field.attrs.bound {
+                explicit.extend(bound);
+            } else if field.attrs.flatten {
+                self.infer_field_type(
+                    field.ty,
+                    &parse_quote!(::derive_debug::__private::DebugFields),
+                );
+                self.infer_field_type(field.ty, &parse_quote!(::derive_debug::DebugDiff));
+            } else if field.attrs.uses_debug() {
+                self.infer_type(field.ty, &parse_quote!(::core::fmt::Debug));
+                if field.attrs.limit.is_some() {
+                    self.infer_field_type(
+                        field.ty,
+                        &parse_quote!(::derive_debug::__private::DebugLimited),
+                    );
+                }
+            } else if field.attrs.bytes.is_some() {
+                self.infer_field_type(field.ty, &parse_quote!(::core::convert::AsRef<[u8]>));
+            } else if field.attrs.units.is_some() {
+                self.infer_type(field.ty, &parse_quote!(::core::fmt::Debug));
+                self.infer_type(field.ty, &parse_quote!(::derive_debug::__private::Number));
+            }
+        }
+        for predicate in explicit {
+            self.insert(predicate.clone());
+        }
+    }
+
+    /// Infers a `bound` for every type parameter, and every associated type of
+    /// a type parameter, mentioned by `ty` outside of `PhantomData`.
+    pub fn infer_type(&mut self, ty: &Type, bound: &Path) {
+        let mut visitor = FindTyParams {
+            params: &self.params,
+            phantom: self.phantom,
+            found: Vec::new(),
+        };
+        visitor.visit_type(ty);
+        for ty in visitor.found {
+            self.insert(parse_quote!(#ty: #bound));
+        }
+    }
+
+    /// Bounds `ty` itself by `bound` if it mentions a type parameter outside of
+    /// `PhantomData`, for traits which the field's type implements as a whole,
+    /// like `[T; 3]: DebugLimited`.
+    pub fn infer_field_type(&mut self, ty: &Type, bound: &Path) {
+        let mut visitor = FindTyParams {
+            params: &self.params,
+            phantom: self.phantom,
+            found: Vec::new(),
+        };
+        visitor.visit_type(ty);
+        if !visitor.found.is_empty() {
+            self.insert(parse_quote!(#ty: #bound));
+        }
+    }
+
+    pub fn extend(&mut self, predicates: &[WherePredicate]) {
+        for predicate in predicates {
+            self.insert(predicate.clone());
+        }
+    }
+
+    fn insert(&mut self, predicate: WherePredicate) {
+        if self.seen.insert(predicate.to_token_stream().to_string()) {
+            self.predicates.push(predicate);
+        }
+    }
+
+    /// The input's generics with the accumulated predicates appended to its
+    /// where-clause.
+    pub fn apply(self, generics: &Generics) -> Generics {
+        let mut generics = generics.clone();
+        generics
+            .make_where_clause()
+            .predicates
+            .extend(self.predicates);
+        generics
+    }
+}
+
+struct FindTyParams<'ast, 'a> {
+    params: &'a BTreeSet<&'a Ident>,
+    phantom: &'a [Path],
+    found: Vec<&'ast TypePath>,
+}
+
+impl<'ast, 'a> Visit<'ast> for FindTyParams<'ast, 'a> {
+    fn visit_type_path(&mut self, ty: &'ast TypePath) {
+        if let Some(qself) = &ty.qself {
+            // A projection like `<T as Trait>::Value` needs the bound on the
+            // whole projection, not on `T`.
+            let mut inner = FindTyParams {
+                params: self.params,
+                phantom: self.phantom,
+                found: Vec::new(),
+            };
+            inner.visit_type(&qself.ty);
+            if !inner.found.is_empty() {
+                self.found.push(ty);
+                return;
+            }
+        } else {
+            if is_phantom_data(&ty.path) || self.is_phantom(&ty.path) {
+                return;
+            }
+            if let Some(first) = ty.path.segments.first() {
+                if ty.path.leading_colon.is_none() && self.params.contains(&first.ident) {
+                    // Either `T` itself or an associated type like `T::Value`.
+                    self.found.push(ty);
+                    return;
+                }
+            }
+        }
+        visit::visit_type_path(self, ty);
+    }
+
+    // `dyn Trait<T>` implements Debug through a supertrait of `Trait`, if at
+    // all, regardless of `T`.
+    fn visit_type_trait_object(&mut self, _ty: &'ast TypeTraitObject) {}
+}
+
+impl FindTyParams<'_, '_> {
+    /// Whether the path names one of the types listed in
+    /// `#[debug(phantom = "...")]`, comparing the last segment only so that
+    /// `TypedId` matches `ids::TypedId<T>`.
+    fn is_phantom(&self, path: &Path) -> bool {
+        let last = match path.segments.last() {
+            Some(segment) => &segment.ident,
+            None => return false,
+        };
+        self.phantom
+            .iter()
+            .any(|phantom| match phantom.segments.last() {
+                Some(segment) => segment.ident == *last,
+                None => false,
+            })
+    }
+}
+
+fn is_phantom_data(path: &Path) -> bool {
+    match path.segments.last() {
+        Some(segment) => segment.ident == "PhantomData",
+        None => false,
+    }
+}
@@ -0,0 +1,154 @@
+//! derive(CustomDisplay): `#[display("...")]` on a struct, or on each variant
+//! of an enum, is a format string whose placeholders name the fields.
+
+use crate::ast::{Field, Input, Style};
+use crate::bound::Bounds;
+use crate::{attr, fmt};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{parse_quote, Data, DeriveInput, Error, Ident, LitStr, Path, Result};
+
+pub fn derive(node: &DeriveInput) -> Result<TokenStream> {
+    if let Data::Union(_) = node.data {
+        return Err(Error::new_spanned(
+            &node.ident,
+            "CustomDisplay does not support unions",
+        ));
+    }
+    let input = Input::from_syn(node)?;
+    let (ident, generics) = match &input {
+        Input::Struct(input) => (input.ident, input.generics),
+        Input::Enum(input) => (input.ident, input.generics),
+    };
+
+    let mut bounds = Bounds::new(generics, &[]);
+    let body = match (&input, &node.data) {
+        (Input::Struct(input), _) => {
+            let lit = attr::display(&node.attrs)?.ok_or_else(|| missing(input.ident))?;
+            let arm = arm(quote!(Self), &lit, input.style, &input.fields, &mut bounds)?;
+            quote!(match self { #arm })
+        }
+        (Input::Enum(input), Data::Enum(data)) => {
+            if let Some(lit) = attr::display(&node.attrs)? {
+                return Err(Error::new(
+                    lit.span(),
+                    "put `display(\"...\")` on each variant of an enum",
+                ));
+            }
+            let mut arms = Vec::new();
+            for (variant, node) in input.variants.iter().zip(&data.variants) {
+                let lit = attr::display(&node.attrs)?.ok_or_else(|| missing(variant.ident))?;
+                let ident = variant.ident;
+                let path = quote!(Self::#ident);
+                arms.push(arm(
+                    path,
+                    &lit,
+                    variant.style,
+                    &variant.fields,
+                    &mut bounds,
+                )?);
+            }
+            if arms.is_empty() {
+                quote!(match *self {})
+            } else {
+                quote!(match self { #(#arms,)* })
+            }
+        }
+        (Input::Enum(_), _) => unreachable!(),
+    };
+
+    let generics = bounds.apply(generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::core::fmt::Display for #ident #ty_generics #where_clause {
+            fn fmt(&self, __formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                #body
+            }
+        }
+    })
+}
+
+fn missing(ident: &Ident) -> Error {
+    Error::new_spanned(ident, "missing `#[display(\"...\")]` attribute")
+}
+
+/// A match arm which binds the fields named in `lit` and writes it.
+///
+/// Tuple fields are referred to by index, `{0}`, which is turned into a
+/// reference to a named argument `_0` so that fields which are not printed do
+/// not count as unused arguments.
+fn arm(
+    path: TokenStream,
+    lit: &LitStr,
+    style: Style,
+    fields: &[Field],
+    bounds: &mut Bounds,
+) -> Result<TokenStream> {
+    let error = |msg: String| Error::new(lit.span(), msg);
+    let placeholders = fmt::parse(&lit.value()).map_err(|msg| error(msg + " in format string"))?;
+
+    let find = |arg: &fmt::Argument| -> Result<usize> {
+        let name = match (arg, style) {
+            (fmt::Argument::Name(name), Style::Named) => name.clone(),
+            (fmt::Argument::Index(index), Style::Tuple) => index.to_string(),
+            (fmt::Argument::Next, _) => {
+                return Err(error(
+                    "`{}` does not refer to a field; name the field as in `{field}` or `{0}`"
+                        .to_owned(),
+                ))
+            }
+            (fmt::Argument::Name(name), _) => name.clone(),
+            (fmt::Argument::Index(index), _) => index.to_string(),
+        };
+        fields
+            .iter()
+            .position(|field| field.name() == name)
+            .ok_or_else(|| error(format!("there is no field `{}`", name)))
+    };
+
+    let mut used = Vec::new();
+    for placeholder in &placeholders {
+        for count in [&placeholder.width, &placeholder.precision] {
+            match count {
+                Some(fmt::Count::Arg(arg)) => used.push(find(arg)?),
+                Some(fmt::Count::Star) => {
+                    return Err(error(
+                        "`.*` does not refer to a field; use `.field$` instead".to_owned(),
+                    ))
+                }
+                Some(fmt::Count::Literal) | None => {}
+            }
+        }
+        let i = find(&placeholder.arg)?;
+        used.push(i);
+        let bound: Path = match placeholder.ty.as_str() {
+            "" => parse_quote!(::core::fmt::Display),
+            "?" | "x?" | "X?" => parse_quote!(::core::fmt::Debug),
+            "x" => parse_quote!(::core::fmt::LowerHex),
+            "X" => parse_quote!(::core::fmt::UpperHex),
+            "o" => parse_quote!(::core::fmt::Octal),
+            "b" => parse_quote!(::core::fmt::Binary),
+            "e" => parse_quote!(::core::fmt::LowerExp),
+            "E" => parse_quote!(::core::fmt::UpperExp),
+            "p" => parse_quote!(::core::fmt::Pointer),
+            _ => unreachable!(),
+        };
+        bounds.infer_type(fields[i].ty, &bound);
+    }
+    used.sort_unstable();
+    used.dedup();
+
+    let members = used.iter().map(|&i| &fields[i].member);
+    let bindings = used.iter().map(|&i| format_ident!("__field{}", i));
+    let names = used.iter().map(|&i| match style {
+        Style::Named => Ident::new(&fields[i].name(), Span::call_site()),
+        _ => format_ident!("_{}", i),
+    });
+    let bindings2 = bindings.clone();
+    let lit = LitStr::new(&fmt::name_indices(&lit.value()), lit.span());
+    Ok(quote! {
+        #path { #(#members: #bindings,)* .. } => {
+            __formatter.write_fmt(::core::format_args!(#lit, #(#names = #bindings2),*))
+        }
+    })
+}
@@ -0,0 +1,569 @@
+use crate::ast::{Enum, Field, Input, Struct, Style};
+use crate::attr::{ByteFormat, Fmt, Redact, Units};
+use crate::bound::Bounds;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use syn::ext::IdentExt;
+use syn::{DeriveInput, GenericParam, Generics, Ident, Result};
+
+pub fn derive(node: &DeriveInput) -> Result<TokenStream> {
+    let input = Input::from_syn(node)?;
+    Ok(match &input {
+        Input::Struct(input) => impl_struct(input),
+        Input::Enum(input) => impl_enum(input),
+    })
+}
+
+fn impl_struct(input: &Struct) -> TokenStream {
+    let ident = input.ident;
+
+    let mut bounds = Bounds::new(input.generics, &input.attrs.phantom);
+    match &input.attrs.bound {
+        Some(bound) => bounds.extend(bound),
+        None if input.attrs.fmt.is_some() => {}
+        None => bounds.infer(&input.fields),
+    }
+    let generics = bounds.apply(input.generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let body = match &input.attrs.fmt {
+        Some(fmt) => depth_limited(
+            input.attrs.max_depth,
+            &struct_name(input),
+            input.style,
+            container_fmt(fmt),
+        ),
+        None => {
+            let helpers = helpers(&input.fields);
+            let pat = pattern(quote!(Self), input.style, &input.fields);
+            let name = struct_name(input);
+            let body = body(name.clone(), input.style, &input.fields);
+            let body = depth_limited(input.attrs.max_depth, &name, input.style, body);
+            quote! {
+                #helpers
+                match self {
+                    #pat => #body,
+                }
+            }
+        }
+    };
+
+    let debug_fields = if input.style == Style::Named && input.attrs.fmt.is_none() {
+        let helpers = helpers(&input.fields);
+        let pat = pattern(quote!(Self), input.style, &input.fields);
+        let Entries {
+            stmts,
+            non_exhaustive,
+        } = entries(input.style, &input.fields);
+        Some(quote! {
+            impl #impl_generics ::derive_debug::__private::DebugFields for #ident #ty_generics #where_clause {
+                fn debug_fields(
+                    &self,
+                    __debug: &mut ::core::fmt::DebugStruct,
+                    __alternate: bool,
+                ) -> bool {
+                    #helpers
+                    match self {
+                        #pat => {
+                            #stmts
+                            #non_exhaustive
+                        }
+                    }
+                }
+            }
+        })
+    } else {
+        None
+    };
+
+    let diff_entries = match &input.attrs.fmt {
+        Some(_) => diff_leaf(),
+        None => {
+            let helpers = helpers(&input.fields);
+            let pat = pattern(quote!(Self), input.style, &input.fields);
+            let stmts = diff_entries(&input.fields);
+            quote! {
+                use ::derive_debug::__private::{ViaDebug as _, ViaDebugDiff as _};
+                #helpers
+                match self {
+                    #pat => {
+                        #(#stmts)*
+                    }
+                }
+            }
+        }
+    };
+    let debug_diff = impl_debug_diff(input.ident, &generics, diff_entries);
+
+    let fmt_kv = if input.attrs.logfmt {
+        Some(quote! {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                /// Prints the fields as logfmt key-value pairs.
+                pub fn fmt_kv(&self) -> impl ::core::fmt::Display + '_ {
+                    ::derive_debug::__private::Logfmt(self)
+                }
+            }
+        })
+    } else {
+        None
+    };
+
+    quote! {
+        impl #impl_generics ::core::fmt::Debug for #ident #ty_generics #where_clause {
+            fn fmt(&self, __formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                #body
+            }
+        }
+
+        #debug_fields
+
+        #debug_diff
+
+        #fmt_kv
+    }
+}
+
+fn impl_enum(input: &Enum) -> TokenStream {
+    let ident = input.ident;
+
+    let mut bounds = Bounds::new(input.generics, &input.attrs.phantom);
+    match &input.attrs.bound {
+        Some(bound) => bounds.extend(bound),
+        None if input.attrs.fmt.is_some() => {}
+        None => {
+            for variant in &input.variants {
+                match &variant.attrs.bound {
+                    Some(bound) => bounds.extend(bound),
+                    None => bounds.infer(&variant.fields),
+                }
+            }
+        }
+    }
+    let generics = bounds.apply(input.generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let fields = input
+        .variants
+        .iter()
+        .flat_map(|variant| &variant.fields)
+        .collect::<Vec<_>>();
+    let helpers = helpers(fields);
+    let discriminants = input.variants.iter().map(|variant| {
+        let ident = variant.ident;
+        quote!(Self::#ident as i128)
+    });
+    let all = quote!(&[#(#discriminants),*]);
+    // Without a `repr`, discriminants are stored as isize.
+    let repr = match &input.repr {
+        Some(repr) => quote!(#repr),
+        None => quote!(isize),
+    };
+    let signed = match &input.repr {
+        Some(repr) => repr.to_string().starts_with('i'),
+        None => true,
+    };
+    let arms = input.variants.iter().map(|variant| {
+        let ident = variant.ident;
+        let pat = pattern(quote!(Self::#ident), variant.style, &variant.fields);
+        let name = ident.unraw().to_string();
+        if let Some(radix) = input.attrs.discriminant {
+            let discriminant = quote! {
+                ::derive_debug::__private::Discriminant {
+                    name: #name,
+                    value: Self::#ident as i128,
+                    all: #all,
+                    bits: ::core::mem::size_of::<#repr>() as u32 * 8,
+                    signed: #signed,
+                    radix: #radix,
+                }
+            };
+            return quote!(#pat => ::core::fmt::Debug::fmt(&#discriminant, __formatter));
+        }
+        let body = body(quote!(#name), variant.style, &variant.fields);
+        let body = depth_limited(input.attrs.max_depth, &quote!(#name), variant.style, body);
+        quote!(#pat => #body)
+    });
+
+    let body = if let Some(fmt) = &input.attrs.fmt {
+        container_fmt(fmt)
+    } else if input.variants.is_empty() {
+        quote!(match *self {})
+    } else {
+        quote! {
+            #helpers
+            match self {
+                #(#arms,)*
+            }
+        }
+    };
+
+    // Variants may differ between the two values, so an enum is compared as
+    // a whole.
+    let debug_diff = impl_debug_diff(ident, &generics, diff_leaf());
+
+    quote! {
+        impl #impl_generics ::core::fmt::Debug for #ident #ty_generics #where_clause {
+            fn fmt(&self, __formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                #body
+            }
+        }
+
+        #debug_diff
+    }
+}
+
+fn impl_debug_diff(ident: &Ident, generics: &Generics, entries: TokenStream) -> TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        impl #impl_generics ::derive_debug::DebugDiff for #ident #ty_generics #where_clause {
+            fn debug_entries(
+                &self,
+                __prefix: &str,
+                __entries: &mut ::derive_debug::__private::Vec<(::derive_debug::__private::String, ::derive_debug::__private::String)>,
+            ) {
+                ::derive_debug::DebugDiff::__debug_entries(self, __prefix, false, __entries)
+            }
+
+            fn __debug_entries(
+                &self,
+                __prefix: &str,
+                __skip_if: bool,
+                __entries: &mut ::derive_debug::__private::Vec<(::derive_debug::__private::String, ::derive_debug::__private::String)>,
+            ) {
+                #entries
+            }
+        }
+    }
+}
+
+/// Body of `debug_entries` for a value compared as a whole.
+fn diff_leaf() -> TokenStream {
+    quote!(::derive_debug::__private::leaf(
+        ::derive_debug::__private::ToOwned::to_owned(__prefix),
+        self,
+        __entries
+    ))
+}
+
+/// Statements of `debug_entries` which append the fields bound by `pattern`
+/// to `__entries`, descending into those whose type implements DebugDiff.
+/// Fields with `skip_if` are left out when it holds, if `__skip_if` is set.
+fn diff_entries(fields: &[Field]) -> Vec<TokenStream> {
+    fields
+        .iter()
+        .enumerate()
+        .filter(|(_, field)| !field.attrs.skip)
+        .map(|(i, field)| {
+            let binding = binding(i);
+            let name = field.printed_name();
+            let path = quote!(::derive_debug::__private::path(__prefix, #name));
+            let entry = if field.attrs.flatten {
+                quote! {
+                    ::derive_debug::DebugDiff::__debug_entries(#binding, __prefix, __skip_if, __entries);
+                }
+            } else if field.attrs.uses_debug() && field.attrs.limit.is_none() {
+                quote! {
+                    (&::derive_debug::__private::Entries(#binding)).debug_entries(#path, __skip_if, __entries);
+                }
+            } else {
+                let value = value(field, &binding);
+                quote!(::derive_debug::__private::leaf(#path, #value, __entries);)
+            };
+            match &field.attrs.skip_if {
+                Some(skip_if) => quote! {
+                    if !(__skip_if && #skip_if(#binding)) {
+                        #entry
+                    }
+                },
+                None => entry,
+            }
+        })
+        .collect()
+}
+
+/// Body of `fmt` for `#[debug(fmt = "...", args...)]` on the container.
+fn container_fmt(fmt: &Fmt) -> TokenStream {
+    let Fmt { lit, args } = fmt;
+    quote!(__formatter.write_fmt(::core::format_args!(#lit, #(#args),*)))
+}
+
+/// Items used by the `value` of some fields, declared locally in `fmt`.
+fn helpers<'a>(fields: impl IntoIterator<Item = &'a Field<'a>>) -> TokenStream {
+    let mut helpers = TokenStream::new();
+    if fields.into_iter().any(|field| field.attrs.with.is_some()) {
+        helpers.extend(quote! {
+            struct __DebugWith<'a, T: ?::core::marker::Sized>(
+                &'a T,
+                fn(&T, &mut ::core::fmt::Formatter) -> ::core::fmt::Result,
+            );
+
+            impl<'a, T: ?::core::marker::Sized> ::core::fmt::Debug for __DebugWith<'a, T> {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                    (self.1)(self.0, f)
+                }
+            }
+        });
+    }
+    helpers
+}
+
+/// Pattern which binds a reference to each field as `__field0`, `__field1`,
+/// and so on.
+fn pattern(path: TokenStream, style: Style, fields: &[Field]) -> TokenStream {
+    let bindings = fields.iter().enumerate().map(|(i, field)| {
+        if field.attrs.skip {
+            quote!(_)
+        } else {
+            let binding = binding(i);
+            quote!(#binding)
+        }
+    });
+    match style {
+        Style::Named => {
+            let members = fields.iter().map(|field| &field.member);
+            quote!(#path { #(#members: #bindings),* })
+        }
+        Style::Tuple => quote!(#path(#(#bindings),*)),
+        Style::Unit => path,
+    }
+}
+
+/// Expression of type `&str` for the name printed for a struct.
+fn struct_name(input: &Struct) -> TokenStream {
+    let name = match &input.attrs.name {
+        Some(name) => name.value(),
+        None => input.ident.unraw().to_string(),
+    };
+    let params = input
+        .generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            GenericParam::Type(param) => {
+                let ident = &param.ident;
+                Some(quote!(::core::any::type_name::<#ident>()))
+            }
+            GenericParam::Const(param) => Some(param.ident.to_token_stream()),
+            GenericParam::Lifetime(_) => None,
+        })
+        .collect::<Vec<_>>();
+    if !input.attrs.type_params || params.is_empty() {
+        return quote!(#name);
+    }
+    let format = format!(
+        "{}<{}>",
+        name.replace('{', "{{").replace('}', "}}"),
+        vec!["{}"; params.len()].join(", "),
+    );
+    quote!(&::derive_debug::__private::format!(#format, #(#params),*))
+}
+
+/// Body of `fmt` for one struct or variant whose fields have been bound by
+/// `pattern`, where `name` is an expression of type `&str`.
+fn body(name: TokenStream, style: Style, fields: &[Field]) -> TokenStream {
+    let builder = match style {
+        Style::Named => quote!(debug_struct),
+        Style::Tuple => quote!(debug_tuple),
+        Style::Unit => return quote!(__formatter.write_str(#name)),
+    };
+    let Entries {
+        stmts,
+        non_exhaustive,
+    } = entries(style, fields);
+    let finish = match non_exhaustive {
+        NonExhaustive::Never => quote!(__builder.finish()),
+        NonExhaustive::Always => quote!(__builder.finish_non_exhaustive()),
+        NonExhaustive::Runtime => quote! {
+            if __non_exhaustive {
+                __builder.finish_non_exhaustive()
+            } else {
+                __builder.finish()
+            }
+        },
+    };
+    let alternate = if fields
+        .iter()
+        .any(|field| field.attrs.alternate.is_some() || field.attrs.flatten)
+    {
+        Some(quote!(let __alternate = __formatter.alternate();))
+    } else {
+        None
+    };
+    quote!({
+        #alternate
+        let mut __builder = __formatter.#builder(#name);
+        let __debug = &mut __builder;
+        #stmts
+        #finish
+    })
+}
+
+/// Wraps `body` so that past `max_depth` nested values with a depth limit, the
+/// value prints as `Name { .. }` instead of recursing any further.
+fn depth_limited(
+    max_depth: Option<usize>,
+    name: &TokenStream,
+    style: Style,
+    body: TokenStream,
+) -> TokenStream {
+    let max_depth = match max_depth {
+        Some(max_depth) if style != Style::Unit => max_depth,
+        _ => return body,
+    };
+    let elided = match style {
+        Style::Named => " { .. }",
+        _ => "(..)",
+    };
+    quote!({
+        let __depth = match ::derive_debug::__private::enter_depth!(#max_depth) {
+            ::core::option::Option::Some(depth) => depth,
+            ::core::option::Option::None => {
+                __formatter.write_str(#name)?;
+                return __formatter.write_str(#elided);
+            }
+        };
+        #body
+    })
+}
+
+/// Statements which add the fields bound by `pattern` to a `&mut DebugStruct`
+/// or `&mut DebugTuple` named `__debug`, given whether `{:#?}` is in use as a
+/// local `__alternate: bool`.
+struct Entries {
+    stmts: TokenStream,
+    non_exhaustive: NonExhaustive,
+}
+
+/// Whether any field was left out, in which case the output ends in `..`.
+enum NonExhaustive {
+    Never,
+    Always,
+    /// Depends on the fields of flattened values, and is held in a local
+    /// `__non_exhaustive: bool` by the time the entries have been added.
+    Runtime,
+}
+
+impl ToTokens for NonExhaustive {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(match self {
+            NonExhaustive::Never => quote!(false),
+            NonExhaustive::Always => quote!(true),
+            NonExhaustive::Runtime => quote!(__non_exhaustive),
+        });
+    }
+}
+
+fn entries(style: Style, fields: &[Field]) -> Entries {
+    let skipped = fields.iter().any(|field| field.attrs.skip);
+    let flattened = fields.iter().any(|field| field.attrs.flatten);
+
+    let mut stmts = TokenStream::new();
+    if flattened {
+        stmts.extend(quote!(let mut __non_exhaustive = #skipped;));
+    }
+    for (i, field) in fields.iter().enumerate() {
+        if field.attrs.skip {
+            continue;
+        }
+        let binding = binding(i);
+        let mut entry = if field.attrs.flatten {
+            quote! {
+                __non_exhaustive |=
+                    ::derive_debug::__private::DebugFields::debug_fields(
+                        #binding,
+                        __debug,
+                        __alternate,
+                    );
+            }
+        } else {
+            let add = |value: TokenStream| match style {
+                Style::Named => {
+                    let name = field.printed_name();
+                    quote!(__debug.field(#name, #value);)
+                }
+                _ => quote!(__debug.field(#value);),
+            };
+            let entry = add(value(field, &binding));
+            match &field.attrs.alternate {
+                Some(alternate) => {
+                    let alternate = add(quote!(&::core::format_args!(#alternate, #binding)));
+                    quote! {
+                        if __alternate {
+                            #alternate
+                        } else {
+                            #entry
+                        }
+                    }
+                }
+                None => entry,
+            }
+        };
+        if let Some(skip_if) = &field.attrs.skip_if {
+            entry = quote! {
+                if !#skip_if(#binding) {
+                    #entry
+                }
+            };
+        }
+        stmts.extend(entry);
+    }
+
+    let non_exhaustive = if flattened {
+        NonExhaustive::Runtime
+    } else if skipped {
+        NonExhaustive::Always
+    } else {
+        NonExhaustive::Never
+    };
+    Entries {
+        stmts,
+        non_exhaustive,
+    }
+}
+
+/// Expression of type `&dyn Debug` which prints one field.
+fn value(field: &Field, binding: &Ident) -> TokenStream {
+    if let Some(redact) = &field.attrs.redact {
+        return match redact {
+            Redact::Placeholder => quote!(&::core::format_args!("<redacted>")),
+            Redact::Len => quote!(&::core::format_args!("<redacted len={}>", #binding.len())),
+        };
+    }
+    if let Some(with) = &field.attrs.with {
+        return quote!(&__DebugWith(#binding, #with));
+    }
+    if let Some(fmt) = &field.attrs.fmt {
+        let Fmt { lit, args } = fmt;
+        return quote!(&::core::format_args!(#lit, #(#args),*));
+    }
+    if let Some(bytes) = &field.attrs.bytes {
+        let wrapper = match bytes {
+            ByteFormat::Hex => quote!(Hex),
+            ByteFormat::Literal => quote!(Bytes),
+        };
+        return quote! {
+            &::derive_debug::__private::#wrapper(::core::convert::AsRef::<[u8]>::as_ref(#binding))
+        };
+    }
+    if let Some(units) = &field.attrs.units {
+        let unit = match units {
+            Units::Bytes => quote!(Bytes),
+            Units::DurationMs => quote!(DurationMs),
+            Units::Percent => quote!(Percent),
+        };
+        return quote! {
+            &::derive_debug::__private::Units(#binding, ::derive_debug::__private::Unit::#unit)
+        };
+    }
+    if let Some(format) = &field.attrs.format {
+        return quote!(&::core::format_args!(#format, #binding));
+    }
+    match &field.attrs.limit {
+        Some(limit) => quote!(&::derive_debug::__private::Limited(#binding, #limit)),
+        None => quote!(#binding),
+    }
+}
+
+fn binding(i: usize) -> Ident {
+    format_ident!("__field{}", i)
+}
@@ -0,0 +1,326 @@
+//! Just enough of a parser for `std::fmt` format strings to check at expansion
+//! time that the placeholders of a format string line up with its arguments.
+
+use std::collections::BTreeSet;
+use syn::{Error, Expr, ExprAssign, ExprPath, LitStr, Result};
+
+/// One `{...}` in a format string.
+pub struct Placeholder {
+    pub arg: Argument,
+    pub width: Option<Count>,
+    pub precision: Option<Count>,
+    /// The formatting trait, as written after the width and precision: `""`
+    /// for Display, `"?"` for Debug, `"x"` for LowerHex, and so on.
+    pub ty: String,
+}
+
+pub enum Argument {
+    /// `{}`: the next positional argument.
+    Next,
+    /// `{0}`
+    Index(usize),
+    /// `{name}`
+    Name(String),
+}
+
+pub enum Count {
+    Literal,
+    /// `{:1$}` or `{:width$}`
+    Arg(Argument),
+    /// `{:.*}`, which takes the precision from the next positional argument.
+    Star,
+}
+
+pub fn parse(format: &str) -> std::result::Result<Vec<Placeholder>, String> {
+    let mut placeholders = Vec::new();
+    let mut chars = format.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+            }
+            '}' => return Err("unmatched `}`".to_owned()),
+            '{' => {
+                let mut inner = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(ch) => inner.push(ch),
+                        None => return Err("unterminated `{`".to_owned()),
+                    }
+                }
+                placeholders.push(parse_placeholder(&inner)?);
+            }
+            _ => {}
+        }
+    }
+    Ok(placeholders)
+}
+
+fn parse_placeholder(inner: &str) -> std::result::Result<Placeholder, String> {
+    let (arg, spec) = match inner.find(':') {
+        Some(colon) => (&inner[..colon], &inner[colon + 1..]),
+        None => (inner, ""),
+    };
+    let arg = parse_argument(arg.trim()).ok_or_else(|| format!("invalid argument `{}`", arg))?;
+
+    let mut rest = spec;
+    // [[fill]align]
+    let mut chars = rest.chars();
+    match (chars.next(), chars.next()) {
+        (Some(fill), Some('<' | '^' | '>')) => rest = &rest[fill.len_utf8() + 1..],
+        (Some('<' | '^' | '>'), _) => rest = &rest[1..],
+        _ => {}
+    }
+    // [sign]['#']['0']
+    rest = rest.strip_prefix(['+', '-']).unwrap_or(rest);
+    rest = rest.strip_prefix('#').unwrap_or(rest);
+    if rest.starts_with('0') && !rest[1..].starts_with('$') {
+        rest = &rest[1..];
+    }
+    // [width]
+    let (width, after) = parse_count(rest);
+    rest = after;
+    // ['.' precision]
+    let mut precision = None;
+    if let Some(after) = rest.strip_prefix('.') {
+        if let Some(after) = after.strip_prefix('*') {
+            precision = Some(Count::Star);
+            rest = after;
+        } else {
+            let (count, after) = parse_count(after);
+            if count.is_none() {
+                return Err(format!("invalid precision in `{{{}}}`", inner));
+            }
+            precision = count;
+            rest = after;
+        }
+    }
+    // [type]
+    match rest {
+        "" | "?" | "x?" | "X?" | "x" | "X" | "o" | "b" | "e" | "E" | "p" => {}
+        _ => return Err(format!("invalid format spec `{{{}}}`", inner)),
+    }
+
+    Ok(Placeholder {
+        arg,
+        width,
+        precision,
+        ty: rest.to_owned(),
+    })
+}
+
+/// Replaces each placeholder which refers to a positional argument by index,
+/// `{0}` or `{0:?}`, with one which refers to a named argument `_0` instead.
+pub fn name_indices(format: &str) -> String {
+    let mut renamed = String::new();
+    let mut chars = format.chars().peekable();
+    while let Some(ch) = chars.next() {
+        renamed.push(ch);
+        match ch {
+            '{' if chars.peek() == Some(&'{') => renamed.push(chars.next().unwrap()),
+            '{' => {
+                let mut inner = String::new();
+                for ch in chars.by_ref() {
+                    if ch == '}' {
+                        break;
+                    }
+                    inner.push(ch);
+                }
+                let end = inner.find(':').unwrap_or(inner.len());
+                let arg = inner[..end].trim();
+                if !arg.is_empty() && arg.bytes().all(|byte| byte.is_ascii_digit()) {
+                    renamed.push('_');
+                }
+                renamed.push_str(&inner);
+                renamed.push('}');
+            }
+            _ => {}
+        }
+    }
+    renamed
+}
+
+fn parse_argument(arg: &str) -> Option<Argument> {
+    if arg.is_empty() {
+        Some(Argument::Next)
+    } else if let Ok(index) = arg.parse() {
+        Some(Argument::Index(index))
+    } else if is_ident(arg) {
+        Some(Argument::Name(arg.to_owned()))
+    } else {
+        None
+    }
+}
+
+/// Parses a width or precision: an integer, `N$` or `name$`.
+fn parse_count(spec: &str) -> (Option<Count>, &str) {
+    let end = spec
+        .find(|ch: char| !(ch.is_alphanumeric() || ch == '_'))
+        .unwrap_or(spec.len());
+    let (word, rest) = spec.split_at(end);
+    if let Some(rest) = rest.strip_prefix('$') {
+        if let Some(arg) = parse_argument(word) {
+            return (Some(Count::Arg(arg)), rest);
+        }
+    }
+    let digits = spec
+        .find(|ch: char| !ch.is_ascii_digit())
+        .unwrap_or(spec.len());
+    if digits > 0 {
+        (Some(Count::Literal), &spec[digits..])
+    } else {
+        (None, spec)
+    }
+}
+
+fn is_ident(s: &str) -> bool {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(ch) if ch == '_' || ch.is_alphabetic() => {}
+        _ => return false,
+    }
+    s != "_" && chars.all(|ch| ch == '_' || ch.is_alphanumeric())
+}
+
+/// Checks the format string of `#[debug = "..."]`, which is given the value of
+/// the field as its only argument.
+pub fn check_single(lit: &LitStr, field: &str) -> Result<()> {
+    let error = |msg: &str| {
+        let msg = format!("{} in format string of field `{}`", msg, field);
+        Error::new(lit.span(), msg)
+    };
+    let placeholders = parse(&lit.value()).map_err(|msg| error(&msg))?;
+    if placeholders.is_empty() {
+        return Err(error("missing placeholder for the value"));
+    }
+    let mut next = 0;
+    for placeholder in &placeholders {
+        for count in [&placeholder.width, &placeholder.precision] {
+            if let Some(Count::Arg(_)) | Some(Count::Star) = count {
+                return Err(error("width or precision taken from another argument"));
+            }
+        }
+        let index = match &placeholder.arg {
+            Argument::Next => {
+                next += 1;
+                next - 1
+            }
+            Argument::Index(index) => *index,
+            Argument::Name(name) => {
+                return Err(error(
+                    &format!("reference to an argument named `{}`", name,),
+                ))
+            }
+        };
+        if index > 0 {
+            return Err(error("more than one positional placeholder"));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that every placeholder in `lit` refers to an argument in `args` and
+/// that every argument is used, the same way `format_args!` would.
+pub fn check(lit: &LitStr, args: &[Expr]) -> Result<()> {
+    let placeholders = parse(&lit.value())
+        .map_err(|msg| Error::new(lit.span(), format!("{} in format string", msg)))?;
+
+    let mut positional = 0;
+    let mut named = Vec::new();
+    for arg in args {
+        match named_arg(arg) {
+            Some(name) => named.push((name, arg)),
+            None if named.is_empty() => positional += 1,
+            None => {
+                return Err(Error::new_spanned(
+                    arg,
+                    "positional arguments cannot follow named arguments",
+                ))
+            }
+        }
+    }
+
+    let mut used_positional = BTreeSet::new();
+    let mut used_named = BTreeSet::new();
+    let mut next = 0;
+    let mut resolve = |arg: &Argument, next: &mut usize| -> Result<()> {
+        let index = match arg {
+            Argument::Next => {
+                *next += 1;
+                *next - 1
+            }
+            Argument::Index(index) => *index,
+            Argument::Name(name) => {
+                if !named.iter().any(|(named, _)| named == name) {
+                    return Err(Error::new(
+                        lit.span(),
+                        format!("there is no argument named `{}`", name),
+                    ));
+                }
+                used_named.insert(name.clone());
+                return Ok(());
+            }
+        };
+        if index >= positional {
+            return Err(Error::new(
+                lit.span(),
+                format!(
+                    "format string refers to positional argument {} but {} given",
+                    index,
+                    match positional {
+                        0 => "no arguments were".to_owned(),
+                        1 => "only 1 argument was".to_owned(),
+                        n => format!("only {} arguments were", n),
+                    },
+                ),
+            ));
+        }
+        used_positional.insert(index);
+        Ok(())
+    };
+    for placeholder in &placeholders {
+        for count in [&placeholder.width, &placeholder.precision] {
+            match count {
+                Some(Count::Arg(arg)) => resolve(arg, &mut next)?,
+                Some(Count::Star) => resolve(&Argument::Next, &mut next)?,
+                Some(Count::Literal) | None => {}
+            }
+        }
+        resolve(&placeholder.arg, &mut next)?;
+    }
+
+    for (index, arg) in args.iter().take(positional).enumerate() {
+        if !used_positional.contains(&index) {
+            return Err(Error::new_spanned(
+                arg,
+                "argument never used by the format string",
+            ));
+        }
+    }
+    for (name, arg) in &named {
+        if !used_named.contains(name) {
+            return Err(Error::new_spanned(
+                arg,
+                "named argument never used by the format string",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The name of an argument written as `name = expr`.
+fn named_arg(arg: &Expr) -> Option<String> {
+    match arg {
+        Expr::Assign(ExprAssign { left, .. }) => match &**left {
+            Expr::Path(ExprPath {
+                path, qself: None, ..
+            }) => path.get_ident().map(ToString::to_string),
+            _ => None,
+        },
+        _ => None,
+    }
+}
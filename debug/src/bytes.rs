@@ -0,0 +1,56 @@
+use core::fmt::{self, Debug, Write};
+
+/// Bytes printed as contiguous lowercase hex, or as an `xxd`-style dump under
+/// `{:#?}`.
+pub struct Hex<'a>(pub &'a [u8]);
+
+/// Bytes printed as an escaped byte string literal, `b"..."`.
+pub struct Bytes<'a>(pub &'a [u8]);
+
+impl<'a> Debug for Hex<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !f.alternate() {
+            for byte in self.0 {
+                write!(f, "{:02x}", byte)?;
+            }
+            return Ok(());
+        }
+        for (i, line) in self.0.chunks(16).enumerate() {
+            if i > 0 {
+                f.write_char('\n')?;
+            }
+            write!(f, "{:08x}:", i * 16)?;
+            for column in 0..16 {
+                if column % 2 == 0 {
+                    f.write_char(' ')?;
+                }
+                match line.get(column) {
+                    Some(byte) => write!(f, "{:02x}", byte)?,
+                    None => f.write_str("  ")?,
+                }
+            }
+            f.write_str("  ")?;
+            for &byte in line {
+                let ch = if byte.is_ascii_graphic() || byte == b' ' {
+                    byte as char
+                } else {
+                    '.'
+                };
+                f.write_char(ch)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Debug for Bytes<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("b\"")?;
+        for &byte in self.0 {
+            for ch in core::ascii::escape_default(byte) {
+                f.write_char(ch as char)?;
+            }
+        }
+        f.write_char('"')
+    }
+}
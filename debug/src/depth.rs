@@ -0,0 +1,34 @@
+use core::cell::Cell;
+use std::thread_local;
+
+thread_local! {
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Counts one level of nesting for as long as it is alive, for types with
+/// `#[debug(max_depth = N)]`.
+///
+/// The count is shared by every such type on the current thread, so that a
+/// cycle running through several types stops at the smallest limit among
+/// them.
+pub struct Depth(());
+
+impl Depth {
+    /// Enters one more level, or returns `None` if `max` levels are already
+    /// being printed.
+    pub fn enter(max: usize) -> Option<Depth> {
+        DEPTH.with(|depth| {
+            if depth.get() >= max {
+                return None;
+            }
+            depth.set(depth.get() + 1);
+            Some(Depth(()))
+        })
+    }
+}
+
+impl Drop for Depth {
+    fn drop(&mut self) {
+        DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
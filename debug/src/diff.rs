@@ -0,0 +1,113 @@
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+
+/// Compares two values field by field through their `Debug` output.
+///
+/// Implemented by derive(CustomDebug). Fields whose own type derives
+/// CustomDebug are compared field by field in turn, under a dotted path like
+/// `limits.max_connections`; every other field is compared as a whole, the
+/// way it is printed in `{:?}`, custom formats included.
+pub trait DebugDiff {
+    /// Appends the path and rendered `Debug` output of every field to
+    /// `entries`, with paths starting from `prefix`.
+    fn debug_entries(&self, prefix: &str, entries: &mut Vec<(String, String)>);
+
+    // Not public API. Like `debug_entries`, but with `skip_if` set, fields
+    // whose `#[debug(skip_if = "...")]` predicate holds are left out the way
+    // they are in `{:?}`. Only logfmt sets it; `debug_diff` needs every field
+    // so that the entries of both values line up.
+    #[doc(hidden)]
+    fn __debug_entries(&self, prefix: &str, _skip_if: bool, entries: &mut Vec<(String, String)>) {
+        self.debug_entries(prefix, entries)
+    }
+
+    /// Lists the fields which print differently in `self` and `other`, one per
+    /// line as `path: old != new`. Empty if both print the same.
+    fn debug_diff(&self, other: &Self) -> String {
+        let mut old = Vec::new();
+        let mut new = Vec::new();
+        self.debug_entries("", &mut old);
+        other.debug_entries("", &mut new);
+        let mut diff = String::new();
+        for ((path, old), (_, new)) in old.into_iter().zip(new) {
+            if old == new {
+                continue;
+            }
+            if !diff.is_empty() {
+                diff.push('\n');
+            }
+            if !path.is_empty() {
+                diff.push_str(&path);
+                diff.push_str(": ");
+            }
+            diff.push_str(&old);
+            diff.push_str(" != ");
+            diff.push_str(&new);
+        }
+        diff
+    }
+}
+
+impl<T: ?Sized + DebugDiff> DebugDiff for &T {
+    fn debug_entries(&self, prefix: &str, entries: &mut Vec<(String, String)>) {
+        (**self).debug_entries(prefix, entries)
+    }
+
+    fn __debug_entries(&self, prefix: &str, skip_if: bool, entries: &mut Vec<(String, String)>) {
+        (**self).__debug_entries(prefix, skip_if, entries)
+    }
+}
+
+impl<T: ?Sized + DebugDiff> DebugDiff for Box<T> {
+    fn debug_entries(&self, prefix: &str, entries: &mut Vec<(String, String)>) {
+        (**self).debug_entries(prefix, entries)
+    }
+
+    fn __debug_entries(&self, prefix: &str, skip_if: bool, entries: &mut Vec<(String, String)>) {
+        (**self).__debug_entries(prefix, skip_if, entries)
+    }
+}
+
+/// `prefix.name`, or just `name` at the top level.
+pub fn path(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{}.{}", prefix, name)
+    }
+}
+
+/// Appends a single entry for a value compared as a whole.
+pub fn leaf<T: ?Sized + Debug>(path: String, value: &T, entries: &mut Vec<(String, String)>) {
+    entries.push((path, format!("{:?}", value)));
+}
+
+// Generated code calls `(&Entries(value)).debug_entries(..)` with both of the
+// traits below in scope. Method resolution picks `ViaDebugDiff` whenever the
+// field's type implements DebugDiff, because it needs one less autoref, and
+// falls back to comparing the `Debug` output otherwise.
+pub struct Entries<'a, T: ?Sized>(pub &'a T);
+
+pub trait ViaDebugDiff {
+    fn debug_entries(&self, path: String, skip_if: bool, entries: &mut Vec<(String, String)>);
+}
+
+impl<T: ?Sized + DebugDiff> ViaDebugDiff for Entries<'_, T> {
+    fn debug_entries(&self, path: String, skip_if: bool, entries: &mut Vec<(String, String)>) {
+        self.0.__debug_entries(&path, skip_if, entries)
+    }
+}
+
+pub trait ViaDebug {
+    fn debug_entries(&self, path: String, skip_if: bool, entries: &mut Vec<(String, String)>);
+}
+
+impl<T: ?Sized + Debug> ViaDebug for &Entries<'_, T> {
+    fn debug_entries(&self, path: String, _skip_if: bool, entries: &mut Vec<(String, String)>) {
+        leaf(path, self.0, entries)
+    }
+}
@@ -0,0 +1,41 @@
+use core::fmt::{self, Debug};
+
+/// A variant of a C-like enum printed with its discriminant, for
+/// `#[debug(discriminant)]`: `SMI = 2` in decimal, or `SMI (0b010)` in
+/// another radix, zero-padded to the width of the largest discriminant.
+///
+/// Outside of decimal, negative discriminants print as the two's complement at
+/// the width of the enum's `repr`, the way they are stored: `-1` in an enum
+/// with `#[repr(i8)]` is `0xff`. In decimal, only a signed `repr` prints
+/// negative values, so that `u128::MAX` does not come out as `-1`.
+pub struct Discriminant<'a> {
+    pub name: &'a str,
+    pub value: i128,
+    pub all: &'a [i128],
+    /// Size of the enum's `repr` in bits.
+    pub bits: u32,
+    /// Whether the enum's `repr` is a signed integer type.
+    pub signed: bool,
+    pub radix: u32,
+}
+
+impl<'a> Debug for Discriminant<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mask = u128::MAX >> (128 - self.bits.clamp(1, 128));
+        let max = self.all.iter().map(|&value| value as u128 & mask).max();
+        let mut width = 1;
+        let mut rest = max.unwrap_or(0) / self.radix as u128;
+        while rest > 0 {
+            width += 1;
+            rest /= self.radix as u128;
+        }
+        let (name, value) = (self.name, self.value as u128 & mask);
+        match self.radix {
+            2 => write!(f, "{} (0b{:0width$b})", name, value, width = width),
+            8 => write!(f, "{} (0o{:0width$o})", name, value, width = width),
+            16 => write!(f, "{} (0x{:0width$x})", name, value, width = width),
+            _ if self.signed => write!(f, "{} = {}", name, self.value),
+            _ => write!(f, "{} = {}", name, value),
+        }
+    }
+}
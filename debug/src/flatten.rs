@@ -0,0 +1,23 @@
+use alloc::boxed::Box;
+use core::fmt::DebugStruct;
+
+/// Implemented by derive(CustomDebug) for structs with named fields, so that a
+/// parent struct can inline their fields with `#[debug(flatten)]`.
+pub trait DebugFields {
+    /// Adds each field to `debug`, and returns whether any were left out by
+    /// `#[debug(skip)]`. The `alternate` flag tells whether the parent is being
+    /// printed with `{:#?}`.
+    fn debug_fields(&self, debug: &mut DebugStruct, alternate: bool) -> bool;
+}
+
+impl<T: ?Sized + DebugFields> DebugFields for &T {
+    fn debug_fields(&self, debug: &mut DebugStruct, alternate: bool) -> bool {
+        (**self).debug_fields(debug, alternate)
+    }
+}
+
+impl<T: ?Sized + DebugFields> DebugFields for Box<T> {
+    fn debug_fields(&self, debug: &mut DebugStruct, alternate: bool) -> bool {
+        (**self).debug_fields(debug, alternate)
+    }
+}
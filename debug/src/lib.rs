@@ -1,8 +1,72 @@
-use proc_macro::TokenStream;
+// Crates with the "proc-macro" crate type can only export procedural macros,
+// but some options of derive(CustomDebug) expand to code which relies on
+// helper types and traits at runtime. The derives live in the derive_debug-impl
+// crate and are re-exported from here next to those helpers, so that users only
+// need to depend on this one crate.
+//
+// Nothing here needs more than `core` and `alloc`, except for the thread local
+// depth count behind `#[debug(max_depth = N)]` and the `#[debug(limit = N)]`
+// impls for HashMap and HashSet, which are only available with the default
+// `std` feature.
 
-#[proc_macro_derive(CustomDebug)]
-pub fn derive(input: TokenStream) -> TokenStream {
-    let _ = input;
+#![no_std]
 
-    unimplemented!()
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+pub use crate::diff::DebugDiff;
+pub use derive_debug_impl::{CustomDebug, CustomDisplay};
+
+mod bytes;
+#[cfg(feature = "std")]
+mod depth;
+mod diff;
+mod discriminant;
+mod flatten;
+mod limit;
+mod logfmt;
+mod units;
+
+// Generated code for `#[debug(max_depth = N)]` enters a level through this
+// macro rather than naming `Depth`, so that without the `std` feature the
+// derive fails with an error that says why.
+#[cfg(feature = "std")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __enter_depth {
+    ($max:expr) => {
+        $crate::__private::Depth::enter($max)
+    };
+}
+
+#[cfg(not(feature = "std"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __enter_depth {
+    ($max:expr) => {
+        ::core::compile_error!("`debug(max_depth)` requires the `std` feature of derive_debug")
+    };
+}
+
+// Not public API. Used by generated code.
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::__enter_depth as enter_depth;
+    pub use crate::bytes::{Bytes, Hex};
+    #[cfg(feature = "std")]
+    pub use crate::depth::Depth;
+    pub use crate::diff::{leaf, path, Entries, ViaDebug, ViaDebugDiff};
+    pub use crate::discriminant::Discriminant;
+    pub use crate::flatten::DebugFields;
+    pub use crate::limit::{DebugLimited, Limited};
+    pub use crate::logfmt::Logfmt;
+    pub use crate::units::{Number, Unit, Units};
+
+    // Generated code names everything else through `::core`, so that it also
+    // compiles in `#![no_std]` crates.
+    pub use alloc::borrow::ToOwned;
+    pub use alloc::format;
+    pub use alloc::string::String;
+    pub use alloc::vec::Vec;
 }
@@ -0,0 +1,149 @@
+use alloc::collections::{BTreeMap, BTreeSet, VecDeque};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Debug};
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+
+/// Types which `#[debug(limit = N)]` knows how to print in truncated form.
+pub trait DebugLimited {
+    fn fmt_limited(&self, limit: usize, f: &mut fmt::Formatter) -> fmt::Result;
+}
+
+/// Prints the value with at most the given number of elements, or characters
+/// for strings.
+pub struct Limited<'a, T: ?Sized>(pub &'a T, pub usize);
+
+impl<'a, T> Debug for Limited<'a, T>
+where
+    T: ?Sized + DebugLimited,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt_limited(self.1, f)
+    }
+}
+
+/// Final entry of a truncated collection.
+struct More(usize);
+
+impl Debug for More {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "... {} more", self.0)
+    }
+}
+
+/// Map entry printed as a single element of a set, so that it can be followed
+/// by `More`.
+struct Entry<'a, K, V>(&'a K, &'a V);
+
+impl<'a, K: Debug, V: Debug> Debug for Entry<'a, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "{:#?}: {:#?}", self.0, self.1)
+        } else {
+            write!(f, "{:?}: {:?}", self.0, self.1)
+        }
+    }
+}
+
+fn list<I>(f: &mut fmt::Formatter, entries: I, limit: usize) -> fmt::Result
+where
+    I: ExactSizeIterator,
+    I::Item: Debug,
+{
+    let len = entries.len();
+    let mut list = f.debug_list();
+    list.entries(entries.take(limit));
+    if len > limit {
+        list.entry(&More(len - limit));
+    }
+    list.finish()
+}
+
+fn set<I>(f: &mut fmt::Formatter, entries: I, limit: usize) -> fmt::Result
+where
+    I: ExactSizeIterator,
+    I::Item: Debug,
+{
+    let len = entries.len();
+    let mut set = f.debug_set();
+    set.entries(entries.take(limit));
+    if len > limit {
+        set.entry(&More(len - limit));
+    }
+    set.finish()
+}
+
+impl DebugLimited for str {
+    fn fmt_limited(&self, limit: usize, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.char_indices().nth(limit) {
+            Some((end, _)) => {
+                Debug::fmt(&self[..end], f)?;
+                f.write_str("...")
+            }
+            None => Debug::fmt(self, f),
+        }
+    }
+}
+
+impl DebugLimited for String {
+    fn fmt_limited(&self, limit: usize, f: &mut fmt::Formatter) -> fmt::Result {
+        self.as_str().fmt_limited(limit, f)
+    }
+}
+
+impl<T: Debug> DebugLimited for [T] {
+    fn fmt_limited(&self, limit: usize, f: &mut fmt::Formatter) -> fmt::Result {
+        list(f, self.iter(), limit)
+    }
+}
+
+impl<T: Debug, const N: usize> DebugLimited for [T; N] {
+    fn fmt_limited(&self, limit: usize, f: &mut fmt::Formatter) -> fmt::Result {
+        list(f, self.iter(), limit)
+    }
+}
+
+impl<T: Debug> DebugLimited for Vec<T> {
+    fn fmt_limited(&self, limit: usize, f: &mut fmt::Formatter) -> fmt::Result {
+        list(f, self.iter(), limit)
+    }
+}
+
+impl<T: Debug> DebugLimited for VecDeque<T> {
+    fn fmt_limited(&self, limit: usize, f: &mut fmt::Formatter) -> fmt::Result {
+        list(f, self.iter(), limit)
+    }
+}
+
+impl<T: Debug> DebugLimited for BTreeSet<T> {
+    fn fmt_limited(&self, limit: usize, f: &mut fmt::Formatter) -> fmt::Result {
+        set(f, self.iter(), limit)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Debug, S> DebugLimited for HashSet<T, S> {
+    fn fmt_limited(&self, limit: usize, f: &mut fmt::Formatter) -> fmt::Result {
+        set(f, self.iter(), limit)
+    }
+}
+
+impl<K: Debug, V: Debug> DebugLimited for BTreeMap<K, V> {
+    fn fmt_limited(&self, limit: usize, f: &mut fmt::Formatter) -> fmt::Result {
+        set(f, self.iter().map(|(k, v)| Entry(k, v)), limit)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: Debug, V: Debug, S> DebugLimited for HashMap<K, V, S> {
+    fn fmt_limited(&self, limit: usize, f: &mut fmt::Formatter) -> fmt::Result {
+        set(f, self.iter().map(|(k, v)| Entry(k, v)), limit)
+    }
+}
+
+impl<T: ?Sized + DebugLimited> DebugLimited for &T {
+    fn fmt_limited(&self, limit: usize, f: &mut fmt::Formatter) -> fmt::Result {
+        (**self).fmt_limited(limit, f)
+    }
+}
@@ -0,0 +1,47 @@
+use crate::diff::DebugDiff;
+use alloc::vec::Vec;
+use core::fmt::{self, Display};
+
+/// Prints the fields of a value as logfmt, `name=F bitmask=0b00011100`, for
+/// `#[debug(logfmt)]`.
+///
+/// Keys and values are the paths and `Debug` output listed by
+/// `DebugDiff::debug_entries`, without the fields left out by `skip_if`.
+/// Strings lose their quotes unless they need them, and any other value
+/// containing a space, `=` or `"` is quoted.
+pub struct Logfmt<'a, T: ?Sized>(pub &'a T);
+
+impl<T: ?Sized + DebugDiff> Display for Logfmt<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut entries = Vec::new();
+        self.0.__debug_entries("", true, &mut entries);
+        for (i, (key, value)) in entries.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            if !key.is_empty() {
+                write!(f, "{}=", key)?;
+            }
+            write_value(f, value)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_value(f: &mut fmt::Formatter, value: &str) -> fmt::Result {
+    let needs_quotes = |s: &str| s.is_empty() || s.contains([' ', '=', '"']);
+    let is_string = value.len() >= 2 && value.starts_with('"') && value.ends_with('"');
+    if is_string {
+        let inner = &value[1..value.len() - 1];
+        if needs_quotes(inner) || inner.contains('\\') {
+            // Already quoted and escaped by Debug.
+            f.write_str(value)
+        } else {
+            f.write_str(inner)
+        }
+    } else if needs_quotes(value) {
+        write!(f, "{:?}", value)
+    } else {
+        f.write_str(value)
+    }
+}
@@ -0,0 +1,83 @@
+use alloc::format;
+use core::fmt::{self, Debug};
+
+/// A number printed in human readable units, followed by the raw value in
+/// parentheses under `{:#?}`.
+pub struct Units<'a, T: ?Sized>(pub &'a T, pub Unit);
+
+pub enum Unit {
+    /// `512 B`, `1.5 MiB`
+    Bytes,
+    /// A number of milliseconds: `250ms`, `2.3s`, `1.5min`, `2h`
+    DurationMs,
+    /// A number which already is a percentage, not a fraction: `42%`
+    Percent,
+}
+
+impl<'a, T: ?Sized + Number + Debug> Debug for Units<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value = self.0.to_f64();
+        match self.1 {
+            Unit::Bytes => {
+                const PREFIXES: [&str; 7] = ["", "Ki", "Mi", "Gi", "Ti", "Pi", "Ei"];
+                let mut scaled = value;
+                let mut prefix = 0;
+                while scaled.abs() >= 1024.0 && prefix + 1 < PREFIXES.len() {
+                    scaled /= 1024.0;
+                    prefix += 1;
+                }
+                write_scaled(f, scaled)?;
+                write!(f, " {}B", PREFIXES[prefix])?;
+            }
+            Unit::DurationMs => {
+                let (scaled, unit) = match value.abs() {
+                    ms if ms < 1000.0 => (value, "ms"),
+                    ms if ms < 60_000.0 => (value / 1000.0, "s"),
+                    ms if ms < 3_600_000.0 => (value / 60_000.0, "min"),
+                    _ => (value / 3_600_000.0, "h"),
+                };
+                write_scaled(f, scaled)?;
+                f.write_str(unit)?;
+            }
+            Unit::Percent => {
+                write_scaled(f, value)?;
+                f.write_str("%")?;
+            }
+        }
+        if f.alternate() {
+            write!(f, " ({:?})", self.0)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes `value` with one decimal, leaving off a trailing `.0`.
+fn write_scaled(f: &mut fmt::Formatter, value: f64) -> fmt::Result {
+    let rounded = format!("{:.1}", value);
+    f.write_str(rounded.strip_suffix(".0").unwrap_or(&rounded))
+}
+
+/// Integer and floating point types which `Units` can print.
+pub trait Number {
+    fn to_f64(&self) -> f64;
+}
+
+macro_rules! number {
+    ($($ty:ty)*) => {
+        $(
+            impl Number for $ty {
+                fn to_f64(&self) -> f64 {
+                    *self as f64
+                }
+            }
+        )*
+    };
+}
+
+number!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize f32 f64);
+
+impl<T: ?Sized + Number> Number for &T {
+    fn to_f64(&self) -> f64 {
+        (**self).to_f64()
+    }
+}
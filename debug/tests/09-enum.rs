@@ -0,0 +1,83 @@
+// Support enums in addition to structs. Each variant is printed the same way
+// that the standard library's derive would print it: unit variants as their
+// bare name, tuple variants with DebugTuple and struct variants with
+// DebugStruct.
+//
+// The #[debug = "..."] attribute works on the fields of any variant, and a
+// #[debug(bound = "...")] attribute on a variant replaces only the bounds
+// inferred from the fields of that variant.
+//
+//     impl<T: Trait, U> Debug for Error<T, U>
+//     where
+//         T::Value: Debug,
+//         U: Debug,
+//     {...}
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+pub trait Trait {
+    type Value;
+}
+
+#[derive(CustomDebug)]
+pub enum Error<T: Trait, U> {
+    Timeout,
+    Code(#[debug = "0x{:04x}"] u16, &'static str),
+    Register {
+        name: &'static str,
+        #[debug = "0b{:08b}"]
+        bitmask: u8,
+    },
+    #[debug(bound = "T::Value: Debug")]
+    Values(Wrapper<T>),
+    Other(U),
+}
+
+pub struct Wrapper<T: Trait>(Vec<T::Value>);
+
+impl<T: Trait> Debug for Wrapper<T>
+where
+    T::Value: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+#[derive(CustomDebug)]
+pub enum Never {}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    struct Id;
+
+    impl Trait for Id {
+        type Value = u8;
+    }
+
+    assert_debug::<Error<Id, u8>>();
+    assert_debug::<Never>();
+
+    let timeout = Error::<Id, u8>::Timeout;
+    assert_eq!(format!("{:?}", timeout), "Timeout");
+
+    let code = Error::<Id, u8>::Code(0x1c, "busy");
+    assert_eq!(format!("{:?}", code), r#"Code(0x001c, "busy")"#);
+
+    let register = Error::<Id, u8>::Register {
+        name: "st0",
+        bitmask: 0b00011100,
+    };
+    assert_eq!(
+        format!("{:?}", register),
+        r#"Register { name: "st0", bitmask: 0b00011100 }"#,
+    );
+
+    let values = Error::<Id, u8>::Values(Wrapper(vec![1, 2]));
+    assert_eq!(format!("{:?}", values), "Values([1, 2])");
+
+    let other = Error::<Id, u8>::Other(7);
+    assert_eq!(format!("{:#?}", other), "Other(\n    7,\n)");
+}
@@ -0,0 +1,44 @@
+// Support tuple structs, printed with DebugTuple, and unit structs, printed as
+// their bare name. Custom formats and bound inference work exactly like they do
+// for structs with named fields.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+#[derive(CustomDebug)]
+pub struct Reg(#[debug = "0x{:04x}"] u16, u8);
+
+#[derive(CustomDebug)]
+pub struct Unit;
+
+#[derive(CustomDebug)]
+pub struct Tagged<T, U>(T, PhantomData<U>);
+
+pub trait Trait {
+    type Value;
+}
+
+#[derive(CustomDebug)]
+pub struct Values<T: Trait>(Vec<T::Value>);
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    struct NotDebug;
+
+    struct Id;
+
+    impl Trait for Id {
+        type Value = u8;
+    }
+
+    assert_debug::<Tagged<u8, NotDebug>>();
+    assert_debug::<Values<Id>>();
+
+    assert_eq!(format!("{:?}", Reg(0x1c, 7)), "Reg(0x001c, 7)");
+    assert_eq!(format!("{:?}", Unit), "Unit");
+
+    let tagged = Tagged::<_, u8>("a", PhantomData);
+    assert_eq!(format!("{:?}", tagged), r#"Tagged("a", PhantomData<u8>)"#);
+}
@@ -0,0 +1,68 @@
+// Fields marked #[debug(skip)] are left out of the output entirely, which then
+// ends in `..` to show that something was omitted. Fields marked
+// #[debug(redact)] are printed as a fixed placeholder instead of their value,
+// and #[debug(redact(len))] additionally shows the length of a string or
+// collection.
+//
+// Neither kind of field contributes a `Debug` bound on the type parameters it
+// mentions.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+#[derive(CustomDebug)]
+pub struct Credentials<T> {
+    user: &'static str,
+    #[debug(redact)]
+    password: String,
+    #[debug(redact(len))]
+    api_key: String,
+    #[debug(skip)]
+    session: T,
+}
+
+#[derive(CustomDebug)]
+pub struct Token(&'static str, #[debug(skip)] u64);
+
+#[derive(CustomDebug)]
+pub enum Auth {
+    Basic {
+        user: &'static str,
+        #[debug(redact)]
+        password: &'static str,
+    },
+    Bearer(#[debug(redact(len))] Vec<u8>),
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    struct NotDebug;
+
+    assert_debug::<Credentials<NotDebug>>();
+
+    let credentials = Credentials {
+        user: "admin",
+        password: "hunter2".to_owned(),
+        api_key: "0123456789abcdef".to_owned(),
+        session: NotDebug,
+    };
+    assert_eq!(
+        format!("{:?}", credentials),
+        r#"Credentials { user: "admin", password: <redacted>, api_key: <redacted len=16>, .. }"#,
+    );
+
+    assert_eq!(format!("{:?}", Token("t", 42)), r#"Token("t", ..)"#);
+
+    let basic = Auth::Basic {
+        user: "admin",
+        password: "hunter2",
+    };
+    assert_eq!(
+        format!("{:?}", basic),
+        r#"Basic { user: "admin", password: <redacted> }"#,
+    );
+
+    let bearer = Auth::Bearer(vec![0; 32]);
+    assert_eq!(format!("{:?}", bearer), "Bearer(<redacted len=32>)");
+}
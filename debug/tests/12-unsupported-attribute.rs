@@ -0,0 +1,13 @@
+// Misspelled or unknown options inside #[debug(...)] are reported on the
+// offending option rather than silently ignored.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Credentials {
+    user: &'static str,
+    #[debug(redcat)]
+    password: String,
+}
+
+fn main() {}
@@ -0,0 +1,65 @@
+// A #[debug(with = "path")] attribute names a function with the same signature
+// as Debug::fmt which prints the field instead of its own Debug impl. This
+// makes it possible to give a readable form to foreign types without wrapping
+// them in a newtype.
+//
+//     fn fmt_duration(value: &Duration, f: &mut fmt::Formatter) -> fmt::Result
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+use std::time::Duration;
+
+mod my_mod {
+    use std::fmt;
+    use std::time::Duration;
+
+    pub fn fmt_duration(value: &Duration, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}ms", value.as_millis())
+    }
+}
+
+fn fmt_len<T>(value: &Vec<T>, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    write!(f, "[{} items]", value.len())
+}
+
+pub struct Opaque;
+
+#[derive(CustomDebug)]
+pub struct Request<T> {
+    #[debug(with = "my_mod::fmt_duration")]
+    timeout: Duration,
+    #[debug(with = "fmt_len")]
+    items: Vec<T>,
+    #[debug(with = "fmt_opaque")]
+    opaque: Opaque,
+}
+
+fn fmt_opaque(_: &Opaque, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    f.write_str("Opaque")
+}
+
+#[derive(CustomDebug)]
+pub enum Event {
+    Elapsed(#[debug(with = "my_mod::fmt_duration")] Duration),
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    struct NotDebug;
+
+    assert_debug::<Request<NotDebug>>();
+
+    let request = Request {
+        timeout: Duration::from_millis(1500),
+        items: vec![NotDebug, NotDebug],
+        opaque: Opaque,
+    };
+    assert_eq!(
+        format!("{:?}", request),
+        "Request { timeout: 1500ms, items: [2 items], opaque: Opaque }",
+    );
+
+    let event = Event::Elapsed(Duration::from_secs(2));
+    assert_eq!(format!("{:?}", event), "Elapsed(2000ms)");
+}
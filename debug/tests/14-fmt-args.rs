@@ -0,0 +1,66 @@
+// A field attribute #[debug(fmt = "...", args...)] prints the field using an
+// arbitrary format string and arguments, which may refer to `self`. This is
+// useful for printing a summary of a large field rather than its contents.
+//
+// The same attribute on the struct itself replaces the whole output.
+//
+// The macro checks while expanding that the placeholders in the format string
+// and the arguments after it line up.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+#[derive(CustomDebug)]
+pub struct Buffer<T> {
+    name: &'static str,
+    #[debug(fmt = "{} items (cap {})", self.items.len(), self.items.capacity())]
+    items: Vec<T>,
+}
+
+#[derive(CustomDebug)]
+#[debug(fmt = "Range({}..{}, step {step})", self.start, self.end, step = self.step)]
+pub struct Range<T> {
+    start: u32,
+    end: u32,
+    step: u32,
+    marker: T,
+}
+
+#[derive(CustomDebug)]
+pub struct Padded {
+    #[debug(fmt = "{:>1$}", self.name, self.width)]
+    name: &'static str,
+    #[debug(skip)]
+    width: usize,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    struct NotDebug;
+
+    assert_debug::<Buffer<NotDebug>>();
+    assert_debug::<Range<NotDebug>>();
+
+    let mut items = Vec::with_capacity(16);
+    items.extend([1u8, 2, 3]);
+    let buffer = Buffer { name: "rx", items };
+    assert_eq!(
+        format!("{:?}", buffer),
+        r#"Buffer { name: "rx", items: 3 items (cap 16) }"#,
+    );
+
+    let range = Range {
+        start: 0,
+        end: 64,
+        step: 8,
+        marker: NotDebug,
+    };
+    assert_eq!(format!("{:?}", range), "Range(0..64, step 8)");
+
+    let padded = Padded {
+        name: "st0",
+        width: 5,
+    };
+    assert_eq!(format!("{:?}", padded), "Padded { name:   st0, .. }");
+}
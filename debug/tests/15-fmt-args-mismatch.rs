@@ -0,0 +1,21 @@
+// The placeholders of a #[debug(fmt = "...", args...)] format string must line
+// up with its arguments, and a mismatch is reported by the derive rather than
+// from somewhere inside of the generated code.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Buffer {
+    #[debug(fmt = "{} items (cap {})", self.items.len())]
+    items: Vec<u8>,
+}
+
+#[derive(CustomDebug)]
+#[debug(fmt = "Range({}..{})", self.start, self.end, self.step)]
+pub struct Range {
+    start: u32,
+    end: u32,
+    step: u32,
+}
+
+fn main() {}
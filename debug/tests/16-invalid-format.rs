@@ -0,0 +1,35 @@
+// A #[debug = "..."] format string is given exactly one argument, the value of
+// the field. Check it while expanding the derive and report problems on the
+// string literal itself, naming the field, instead of letting the generated
+// format_args! fail with an error that points at the derive.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct NoPlaceholder {
+    #[debug = "0b"]
+    bitmask: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct TwoPlaceholders {
+    #[debug = "{:x} {:b}"]
+    bitmask: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct BadSpec {
+    #[debug = "0b{:08q}"]
+    bitmask: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Unterminated(#[debug = "0x{:04x"] u16);
+
+#[derive(CustomDebug)]
+pub struct RepeatedOk {
+    #[debug = "{0:#x} ({0})"]
+    bitmask: u8,
+}
+
+fn main() {}
@@ -0,0 +1,41 @@
+// Control the names that appear in the output independently of the Rust
+// identifiers: #[debug(rename = "...")] on a field, #[debug(name = "...")] on
+// the struct, and #[debug(type_params)] to include the concrete generic
+// arguments in the struct's name as given by std::any::type_name.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(name = "Reg")]
+pub struct Register {
+    #[debug(rename = "mask")]
+    bitmask: u8,
+    r#type: &'static str,
+}
+
+#[derive(CustomDebug)]
+#[debug(type_params)]
+pub struct Field<T> {
+    value: T,
+}
+
+#[derive(CustomDebug)]
+#[debug(name = "Buf", type_params)]
+pub struct Buffer<'a, T, const N: usize>(&'a [T; N]);
+
+fn main() {
+    let register = Register {
+        bitmask: 0b00011100,
+        r#type: "gpr",
+    };
+    assert_eq!(
+        format!("{:?}", register),
+        r#"Reg { mask: 28, type: "gpr" }"#,
+    );
+
+    let field = Field { value: 7u8 };
+    assert_eq!(format!("{:?}", field), "Field<u8> { value: 7 }");
+
+    let buffer = Buffer(&[1u16, 2]);
+    assert_eq!(format!("{:?}", buffer), "Buf<u16, 2>([1, 2])");
+}
@@ -0,0 +1,91 @@
+// A field attribute #[debug(limit = N)] truncates long collections to their
+// first N elements followed by a count of the ones left out, and long strings
+// to their first N characters followed by an ellipsis.
+//
+// This relies on a trait implemented at runtime for the supported types, which
+// is why derive_debug is split into a regular library crate that re-exports
+// the derive macro from derive_debug-impl. A type parameter printed with a
+// limit gets a `DebugLimited` bound on top of `Debug`.
+
+use derive_debug::CustomDebug;
+use std::collections::BTreeMap;
+
+#[derive(CustomDebug)]
+pub struct Packet<'a> {
+    #[debug(limit = 4)]
+    payload: Vec<u8>,
+    #[debug(limit = 2)]
+    header: &'a [u16],
+    #[debug(limit = 5)]
+    name: String,
+    #[debug(limit = 1)]
+    options: BTreeMap<&'static str, u8>,
+    #[debug(limit = 8)]
+    short: [u8; 2],
+}
+
+#[derive(CustomDebug)]
+pub struct Page<T> {
+    #[debug(limit = 2)]
+    items: T,
+}
+
+fn main() {
+    let mut options = BTreeMap::new();
+    options.insert("mtu", 1);
+    options.insert("ttl", 64);
+
+    let packet = Packet {
+        payload: vec![0; 4096],
+        header: &[1, 2, 3],
+        name: "loopback".to_owned(),
+        options,
+        short: [1, 2],
+    };
+
+    assert_eq!(
+        format!("{:?}", packet),
+        concat!(
+            "Packet { ",
+            "payload: [0, 0, 0, 0, ... 4092 more], ",
+            "header: [1, 2, ... 1 more], ",
+            r#"name: "loopb"..., "#,
+            r#"options: {"mtu": 1, ... 1 more}, "#,
+            "short: [1, 2] ",
+            "}",
+        ),
+    );
+
+    let expected = r#"Packet {
+    payload: [
+        0,
+        0,
+        0,
+        0,
+        ... 4092 more,
+    ],
+    header: [
+        1,
+        2,
+        ... 1 more,
+    ],
+    name: "loopb"...,
+    options: {
+        "mtu": 1,
+        ... 1 more,
+    },
+    short: [
+        1,
+        2,
+    ],
+}"#;
+    assert_eq!(format!("{:#?}", packet), expected);
+
+    let page = Page {
+        items: vec!["a", "b", "c"],
+    };
+    assert_eq!(
+        format!("{:?}", page),
+        r#"Page { items: ["a", "b", ... 1 more] }"#,
+    );
+}
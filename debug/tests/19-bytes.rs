@@ -0,0 +1,52 @@
+// Byte buffers get two dedicated renderings. #[debug(hex)] prints contiguous
+// lowercase hex, or an xxd-style dump with offsets and ASCII under {:#?}.
+// #[debug(bytes)] prints an escaped byte string literal.
+//
+// Both accept [u8; N], &[u8] and Vec<u8>, or any other AsRef<[u8]>, including
+// a type parameter.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Packet<'a> {
+    #[debug(hex)]
+    magic: [u8; 4],
+    #[debug(bytes)]
+    tag: &'a [u8],
+    #[debug(hex)]
+    payload: Vec<u8>,
+}
+
+#[derive(CustomDebug)]
+pub struct Frame<T> {
+    #[debug(hex)]
+    raw: T,
+}
+
+fn main() {
+    let packet = Packet {
+        magic: [0xca, 0xfe, 0xba, 0xbe],
+        tag: b"GET \"/\"\r\n\x00",
+        payload: b"Hello, world! 0123456789\xff".to_vec(),
+    };
+
+    assert_eq!(
+        format!("{:?}", packet),
+        concat!(
+            "Packet { magic: cafebabe, ",
+            r#"tag: b"GET \"/\"\r\n\x00", "#,
+            "payload: 48656c6c6f2c20776f726c64212030313233343536373839ff }",
+        ),
+    );
+
+    let expected = r#"Packet {
+    magic: 00000000: cafe babe                                ....,
+    tag: b"GET \"/\"\r\n\x00",
+    payload: 00000000: 4865 6c6c 6f2c 2077 6f72 6c64 2120 3031  Hello, world! 01
+    00000010: 3233 3435 3637 3839 ff                   23456789.,
+}"#;
+    assert_eq!(format!("{:#?}", packet), expected);
+
+    let frame = Frame { raw: vec![0xca, 0xfe] };
+    assert_eq!(format!("{:?}", frame), "Frame { raw: cafe }");
+}
@@ -0,0 +1,48 @@
+// A field attribute #[debug(skip_if = "path")] leaves the field out of the
+// output whenever the predicate returns true for its value, with the same
+// meaning as serde's skip_serializing_if. Unlike #[debug(skip)], the output
+// does not end in `..` because nothing is being hidden.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Command {
+    executable: String,
+    #[debug(skip_if = "Vec::is_empty")]
+    args: Vec<String>,
+    #[debug(skip_if = "Option::is_none")]
+    current_dir: Option<String>,
+    #[debug(skip_if = "is_zero")]
+    timeout: u64,
+}
+
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+#[derive(CustomDebug)]
+pub struct Pair(#[debug(skip_if = "Option::is_none")] Option<u8>, u8);
+
+fn main() {
+    let command = Command {
+        executable: "cargo".to_owned(),
+        args: Vec::new(),
+        current_dir: None,
+        timeout: 0,
+    };
+    assert_eq!(format!("{:?}", command), r#"Command { executable: "cargo" }"#);
+
+    let command = Command {
+        executable: "cargo".to_owned(),
+        args: vec!["build".to_owned()],
+        current_dir: Some("..".to_owned()),
+        timeout: 30,
+    };
+    assert_eq!(
+        format!("{:?}", command),
+        r#"Command { executable: "cargo", args: ["build"], current_dir: Some(".."), timeout: 30 }"#,
+    );
+
+    assert_eq!(format!("{:?}", Pair(None, 1)), "Pair(1)");
+    assert_eq!(format!("{:?}", Pair(Some(0), 1)), "Pair(Some(0), 1)");
+}
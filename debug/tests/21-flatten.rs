@@ -0,0 +1,93 @@
+// A field attribute #[debug(flatten)] inlines the fields of a nested struct
+// into the output of its parent instead of nesting them.
+//
+// This works through a helper trait which every derive(CustomDebug) on a
+// struct with named fields implements alongside Debug, for writing its fields
+// into a DebugStruct that somebody else has started.
+//
+//     impl DebugFields for Limits {
+//         fn debug_fields(&self, debug: &mut DebugStruct, alternate: bool) -> bool {...}
+//     }
+//
+// A flattened field of a generic type needs that trait, and DebugDiff, rather
+// than Debug.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Config {
+    name: &'static str,
+    #[debug(flatten)]
+    network: Network,
+    #[debug(flatten)]
+    limits: Box<Limits<u32>>,
+}
+
+#[derive(CustomDebug)]
+pub struct Network {
+    #[debug(rename = "net.port")]
+    port: u16,
+    #[debug(flatten)]
+    tls: Tls,
+}
+
+#[derive(CustomDebug)]
+pub struct Tls {
+    enabled: bool,
+    #[debug(skip)]
+    key: Vec<u8>,
+}
+
+#[derive(CustomDebug)]
+pub struct Limits<T> {
+    max_connections: T,
+}
+
+#[derive(CustomDebug)]
+pub struct Wrapper<T> {
+    id: u32,
+    #[debug(flatten)]
+    inner: T,
+}
+
+fn main() {
+    let config = Config {
+        name: "api",
+        network: Network {
+            port: 8080,
+            tls: Tls {
+                enabled: true,
+                key: vec![1, 2, 3],
+            },
+        },
+        limits: Box::new(Limits {
+            max_connections: 64,
+        }),
+    };
+
+    assert_eq!(
+        format!("{:?}", config),
+        r#"Config { name: "api", net.port: 8080, enabled: true, max_connections: 64, .. }"#,
+    );
+
+    let network = Network {
+        port: 443,
+        tls: Tls {
+            enabled: false,
+            key: Vec::new(),
+        },
+    };
+    assert_eq!(
+        format!("{:?}", network),
+        "Network { net.port: 443, enabled: false, .. }",
+    );
+
+    let wrapper = Wrapper {
+        id: 1,
+        inner: Limits { max_connections: 8 },
+    };
+    assert_eq!(
+        format!("{:?}", wrapper),
+        "Wrapper { id: 1, max_connections: 8 }",
+    );
+}
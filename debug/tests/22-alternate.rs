@@ -0,0 +1,43 @@
+// A field attribute #[debug(alternate = "...")] gives a second format string
+// which is used instead of the field's usual output when the struct is printed
+// with the alternate flag, `{:#?}`. This is handy for showing a compact value
+// in one-line logs and a more detailed breakdown in pretty-printed output.
+//
+// Generate a branch on Formatter::alternate() for each such field. The
+// alternate format is checked the same way as #[debug = "..."], so it needs
+// exactly one placeholder for the value.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Field {
+    name: &'static str,
+    #[debug = "0x{:x}"]
+    #[debug(alternate = "0b{:08b}")]
+    bitmask: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Wrapper(#[debug(alternate = "{:.3}")] f64);
+
+fn main() {
+    let f = Field {
+        name: "F",
+        bitmask: 0b00011100,
+    };
+
+    let debug = format!("{:?}", f);
+    assert_eq!(debug, r#"Field { name: "F", bitmask: 0x1c }"#);
+
+    let pretty = format!("{:#?}", f);
+    let expected = r#"
+Field {
+    name: "F",
+    bitmask: 0b00011100,
+}"#;
+    assert_eq!(pretty, &expected[1..]);
+
+    let w = Wrapper(1.0 / 3.0);
+    assert_eq!(format!("{:?}", w), "Wrapper(0.3333333333333333)");
+    assert_eq!(format!("{:#?}", w), "Wrapper(\n    0.333,\n)");
+}
@@ -0,0 +1,82 @@
+// Following on from 08-escape-hatch, accept `debug(bound = "...")` on
+// individual fields too. A field-level bound replaces only the bounds that
+// would have been inferred from that field's type, so the other fields keep
+// their inferred bounds.
+//
+// Also accept a struct-level `debug(phantom = "...")` naming wrapper types
+// which, like PhantomData in 05-phantom-data, print without requiring their
+// type arguments to implement Debug. A type parameter which only appears
+// inside of such a wrapper gets no `T: Debug` bound.
+//
+//     impl<T: Trait, U> Debug for Row<T, U>
+//     where
+//         U: Debug,
+//         T::Value: Debug,
+//     {...}
+
+use derive_debug::CustomDebug;
+use std::fmt::{self, Debug};
+use std::marker::PhantomData;
+
+pub trait Trait {
+    type Value;
+}
+
+pub struct TypedId<T> {
+    raw: u64,
+    marker: PhantomData<T>,
+}
+
+impl<T> Debug for TypedId<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{}", self.raw)
+    }
+}
+
+pub struct Lookup<T: Trait> {
+    value: T::Value,
+}
+
+impl<T: Trait> Debug for Lookup<T>
+where
+    T::Value: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Debug::fmt(&self.value, f)
+    }
+}
+
+#[derive(CustomDebug)]
+#[debug(phantom = "TypedId")]
+pub struct Row<T: Trait, U> {
+    id: TypedId<T>,
+    #[debug(bound = "T::Value: Debug")]
+    lookup: Lookup<T>,
+    extra: U,
+}
+
+fn assert_debug<F: Debug>() {}
+
+struct Users;
+
+impl Trait for Users {
+    type Value = String;
+}
+
+fn main() {
+    // Users does not implement Debug, but does not need to.
+    assert_debug::<Row<Users, u8>>();
+
+    let row = Row::<Users, _> {
+        id: TypedId {
+            raw: 7,
+            marker: PhantomData,
+        },
+        lookup: Lookup {
+            value: "alice".to_owned(),
+        },
+        extra: 1u8,
+    };
+    let debug = format!("{:?}", row);
+    assert_eq!(debug, r#"Row { id: #7, lookup: "alice", extra: 1 }"#);
+}
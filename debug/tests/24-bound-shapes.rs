@@ -0,0 +1,119 @@
+// The bound inference from 04 through 07 should keep working on the other
+// shapes generics come in:
+//
+//   - const generic parameters, which need no bound, as in arrays `[T; N]`;
+//   - parameters with defaults, which must not be repeated in the impl;
+//   - higher-ranked bounds and associated-type equality constraints already
+//     present in the where-clause, which are kept as they are;
+//   - trait objects like `Box<dyn Source<T>>`, which implement Debug through
+//     a supertrait if at all, so they say nothing about `T`;
+//   - associated types nested deep inside other types, `Option<Vec<T::Value>>`,
+//     including the fully qualified `<T as Trait>::Value` spelling.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+#[derive(CustomDebug)]
+pub struct Buffer<T, const N: usize> {
+    data: [T; N],
+    len: usize,
+}
+
+#[derive(CustomDebug)]
+pub struct Defaults<T = String, const N: usize = 2> {
+    items: [T; N],
+}
+
+#[derive(CustomDebug)]
+pub struct Borrowing<I>
+where
+    for<'a> &'a I: IntoIterator,
+{
+    inner: I,
+}
+
+#[derive(CustomDebug)]
+pub struct Pairs<I>
+where
+    I: Iterator<Item = (u8, u8)>,
+{
+    iter: I,
+    last: Option<I::Item>,
+}
+
+pub trait Source<T>: Debug {
+    fn get(&self) -> T;
+}
+
+#[derive(Debug)]
+pub struct Constant;
+
+impl<T: Default> Source<T> for Constant {
+    fn get(&self) -> T {
+        T::default()
+    }
+}
+
+#[derive(CustomDebug)]
+pub struct Scene<T> {
+    sources: Vec<Box<dyn Source<T>>>,
+}
+
+pub trait Trait {
+    type Value;
+}
+
+#[derive(CustomDebug)]
+pub struct Lookup<T: Trait> {
+    values: Option<Vec<T::Value>>,
+    qualified: Option<<T as Trait>::Value>,
+}
+
+fn assert_debug<F: Debug>() {}
+
+#[derive(Default)]
+struct NotDebug;
+
+impl Trait for NotDebug {
+    type Value = u8;
+}
+
+fn main() {
+    assert_debug::<Buffer<u8, 4>>();
+    assert_debug::<Defaults>();
+    assert_debug::<Borrowing<Vec<u8>>>();
+    assert_debug::<Pairs<std::vec::IntoIter<(u8, u8)>>>();
+    assert_debug::<Scene<NotDebug>>();
+    assert_debug::<Lookup<NotDebug>>();
+
+    let buffer = Buffer {
+        data: [1u8, 2, 3],
+        len: 2,
+    };
+    assert_eq!(format!("{:?}", buffer), "Buffer { data: [1, 2, 3], len: 2 }");
+
+    let defaults: Defaults = Defaults {
+        items: ["a".to_owned(), "b".to_owned()],
+    };
+    assert_eq!(format!("{:?}", defaults), r#"Defaults { items: ["a", "b"] }"#);
+
+    let borrowing = Borrowing { inner: vec![1u8] };
+    assert_eq!(format!("{:?}", borrowing), "Borrowing { inner: [1] }");
+
+    let pairs = Pairs {
+        iter: Vec::new().into_iter(),
+        last: Some((1, 2)),
+    };
+    assert_eq!(format!("{:?}", pairs), "Pairs { iter: IntoIter([]), last: Some((1, 2)) }");
+
+    let scene = Scene::<NotDebug> {
+        sources: vec![Box::new(Constant)],
+    };
+    assert_eq!(format!("{:?}", scene), "Scene { sources: [Constant] }");
+
+    let lookup = Lookup::<NotDebug> {
+        values: Some(vec![1, 2]),
+        qualified: None,
+    };
+    assert_eq!(format!("{:?}", lookup), "Lookup { values: Some([1, 2]), qualified: None }");
+}
@@ -0,0 +1,56 @@
+// Types like One<T> and Two<T> from 06-bound-trouble can form arbitrarily deep
+// or even cyclic graphs once they hold each other through Rc and RefCell.
+// Printing a cycle with the standard Debug builders recurses until the stack
+// overflows.
+//
+// Support a struct-level #[debug(max_depth = N)] which stops printing nested
+// values past N levels, writing `One { .. }` in their place. The depth is
+// tracked at runtime through a thread-local counter in the derive_debug crate,
+// which every type with a max_depth attribute increments while it is being
+// printed.
+
+use derive_debug::CustomDebug;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(CustomDebug)]
+#[debug(max_depth = 3)]
+pub struct One<T> {
+    value: T,
+    two: RefCell<Option<Rc<Two<T>>>>,
+}
+
+#[derive(CustomDebug)]
+#[debug(max_depth = 3)]
+pub struct Two<T> {
+    one: Rc<One<T>>,
+}
+
+#[derive(CustomDebug)]
+#[debug(max_depth = 1)]
+pub struct Shallow(Option<Box<Shallow>>);
+
+fn main() {
+    let one = Rc::new(One {
+        value: 1u8,
+        two: RefCell::new(None),
+    });
+    let two = Rc::new(Two { one: one.clone() });
+    *one.two.borrow_mut() = Some(two);
+
+    let debug = format!("{:?}", one);
+    let expected = concat!(
+        "One { value: 1, two: RefCell { value: Some(",
+        "Two { one: One { value: 1, two: RefCell { value: Some(",
+        "Two { .. }",
+        ") } } }",
+        ") } }",
+    );
+    assert_eq!(debug, expected);
+
+    // Break the cycle so that it gets dropped.
+    one.two.borrow_mut().take();
+
+    let shallow = Shallow(Some(Box::new(Shallow(None))));
+    assert_eq!(format!("{:?}", shallow), "Shallow(Some(Shallow(..)))");
+}
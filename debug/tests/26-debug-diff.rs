@@ -0,0 +1,113 @@
+// Comparing the Debug output of two large structs by eye is tedious. Alongside
+// Debug, implement derive_debug::DebugDiff, whose `debug_diff` method lists
+// only the fields that print differently, one per line:
+//
+//     name: "F" != "G"
+//     limits.max_connections: 100 != 200
+//
+// Fields whose type also derives CustomDebug are compared field by field under
+// a dotted path. Every other field is compared the way it is printed, so
+// custom formats like #[debug = "0b{:08b}"] decide what counts as a
+// difference, and skipped fields are ignored. Fields with skip_if are always
+// compared, since they may be left out on one side only.
+//
+// Types can also implement DebugDiff by hand, listing their own entries.
+
+use derive_debug::{CustomDebug, DebugDiff};
+
+#[derive(CustomDebug, Clone)]
+pub struct Config {
+    name: &'static str,
+    #[debug = "0b{:08b}"]
+    bitmask: u8,
+    #[debug(skip)]
+    generation: u64,
+    #[debug(rename = "net")]
+    network: Network,
+    #[debug(flatten)]
+    limits: Limits,
+    mode: Mode,
+    #[debug(skip_if = "Option::is_none")]
+    owner: Option<&'static str>,
+    version: Version,
+}
+
+#[derive(Debug, Clone)]
+pub struct Version(u8, u8);
+
+impl DebugDiff for Version {
+    fn debug_entries(&self, prefix: &str, entries: &mut Vec<(String, String)>) {
+        entries.push((format!("{}.major", prefix), self.0.to_string()));
+        entries.push((format!("{}.minor", prefix), self.1.to_string()));
+    }
+}
+
+#[derive(CustomDebug, Clone)]
+pub struct Network {
+    port: u16,
+    tls: Tls,
+}
+
+#[derive(CustomDebug, Clone)]
+pub struct Tls(bool);
+
+#[derive(CustomDebug, Clone)]
+pub struct Limits {
+    max_connections: u32,
+    #[debug = "{:.1}"]
+    timeout: f64,
+}
+
+#[derive(CustomDebug, Clone)]
+pub enum Mode {
+    Fast,
+    Careful { retries: u8 },
+}
+
+fn main() {
+    let old = Config {
+        name: "F",
+        bitmask: 0b00011100,
+        generation: 1,
+        network: Network {
+            port: 80,
+            tls: Tls(false),
+        },
+        limits: Limits {
+            max_connections: 100,
+            timeout: 1.0,
+        },
+        mode: Mode::Fast,
+        owner: None,
+        version: Version(1, 0),
+    };
+
+    assert_eq!(old.debug_diff(&old.clone()), "");
+
+    let mut new = old.clone();
+    new.generation = 2;
+    new.limits.timeout = 1.01;
+    assert_eq!(old.debug_diff(&new), "");
+
+    new.bitmask = 0b00011000;
+    new.network.tls = Tls(true);
+    new.limits.max_connections = 200;
+    new.mode = Mode::Careful { retries: 3 };
+    new.owner = Some("ops");
+    new.version.1 = 1;
+    let expected = [
+        "bitmask: 0b00011100 != 0b00011000",
+        "net.tls.0: false != true",
+        "max_connections: 100 != 200",
+        "mode: Fast != Careful { retries: 3 }",
+        r#"owner: None != Some("ops")"#,
+        "version.minor: 0 != 1",
+    ];
+    assert_eq!(old.debug_diff(&new), expected.join("\n"));
+
+    assert_eq!(Mode::Fast.debug_diff(&Mode::Fast), "");
+    assert_eq!(
+        Mode::Fast.debug_diff(&Mode::Careful { retries: 1 }),
+        "Fast != Careful { retries: 1 }",
+    );
+}
@@ -0,0 +1,66 @@
+// Structured loggers often expect logfmt, a line of space separated key=value
+// pairs, rather than Debug output. Support an opt-in struct-level
+// #[debug(logfmt)] which additionally generates an inherent method:
+//
+//     impl Field {
+//         pub fn fmt_kv(&self) -> impl Display + '_ {...}
+//     }
+//
+// The keys and values are the same ones DebugDiff compares: nested
+// CustomDebug types contribute dotted keys, and custom formats and renames
+// apply. Strings are printed without quotes where possible, and values which
+// contain spaces are quoted. Like in Debug output, fields with skip_if are
+// left out whenever their predicate holds.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(logfmt)]
+pub struct Field {
+    name: &'static str,
+    #[debug = "0b{:08b}"]
+    bitmask: u8,
+    #[debug(skip)]
+    secret: u64,
+    origin: Origin,
+    label: String,
+    tags: Vec<&'static str>,
+    #[debug(skip_if = "Option::is_none")]
+    owner: Option<&'static str>,
+}
+
+#[derive(CustomDebug)]
+pub struct Origin {
+    file: &'static str,
+    #[debug(rename = "ln")]
+    line: u32,
+}
+
+fn main() {
+    let f = Field {
+        name: "F",
+        bitmask: 0b00011100,
+        secret: 1,
+        origin: Origin {
+            file: "src/lib.rs",
+            line: 7,
+        },
+        label: "two words".to_owned(),
+        tags: vec!["a"],
+        owner: None,
+    };
+
+    let kv = f.fmt_kv().to_string();
+    let expected = concat!(
+        "name=F bitmask=0b00011100 origin.file=src/lib.rs origin.ln=7 ",
+        r#"label="two words" tags="[\"a\"]""#,
+    );
+    assert_eq!(kv, expected);
+
+    let f = Field {
+        owner: Some("ops"),
+        ..f
+    };
+    let kv = f.fmt_kv().to_string();
+    assert!(kv.ends_with(r#" owner="Some(\"ops\")""#), "{}", kv);
+}
@@ -0,0 +1,79 @@
+// Types which derive CustomDebug often want a Display impl as well, error
+// enums in particular. Add a second derive, CustomDisplay, to the same crate.
+//
+// The format string goes in a #[display("...")] attribute on the struct, or on
+// each variant of an enum, and refers to fields inline by name, or by index
+// for tuple fields:
+//
+//     #[derive(CustomDisplay)]
+//     #[display("{name} ({bitmask:#b})")]
+//     pub struct Field {
+//         name: &'static str,
+//         bitmask: u8,
+//     }
+//
+// Bounds are inferred the same way as for CustomDebug, except that each type
+// parameter gets a bound on the formatting trait its placeholder uses, such
+// as `T: Display` for `{value}` or `T: LowerHex` for `{value:x}`.
+
+use derive_debug::{CustomDebug, CustomDisplay};
+use std::fmt::{Debug, Display};
+
+#[derive(CustomDebug, CustomDisplay)]
+#[display("{name} ({bitmask:#b})")]
+pub struct Field {
+    name: &'static str,
+    bitmask: u8,
+}
+
+#[derive(CustomDisplay)]
+#[display("{0}={1:x}")]
+pub struct Pair<K, V>(K, V);
+
+#[derive(CustomDisplay)]
+#[display("{label}")]
+pub struct Labelled<T> {
+    label: &'static str,
+    value: T,
+}
+
+#[derive(CustomDebug, CustomDisplay)]
+pub enum Error {
+    #[display("invalid bitmask {mask:#010b} for {field}")]
+    InvalidMask { field: Field, mask: u8 },
+    #[display("field `{1}` is out of range {0:?}")]
+    OutOfRange(std::ops::Range<u32>, &'static str),
+    #[display("unexpected end of input")]
+    Eof,
+}
+
+fn assert_display<T: Display>() {}
+fn assert_debug<T: Debug>() {}
+
+struct NotDisplay;
+
+fn main() {
+    let f = Field {
+        name: "F",
+        bitmask: 0b00011100,
+    };
+    assert_eq!(f.to_string(), "F (0b11100)");
+
+    assert_eq!(Pair("x", 255).to_string(), "x=ff");
+
+    assert_display::<Labelled<NotDisplay>>();
+    let labelled = Labelled {
+        label: "unused",
+        value: NotDisplay,
+    };
+    assert_eq!(labelled.to_string(), "unused");
+
+    assert_debug::<Error>();
+    let error = Error::InvalidMask { field: f, mask: 3 };
+    assert_eq!(error.to_string(), "invalid bitmask 0b00000011 for F (0b11100)");
+    assert_eq!(
+        Error::OutOfRange(0..8, "bitmask").to_string(),
+        "field `bitmask` is out of range 0..8",
+    );
+    assert_eq!(Error::Eof.to_string(), "unexpected end of input");
+}
@@ -0,0 +1,65 @@
+// Byte counts and durations in milliseconds are hard to read as raw numbers.
+// Support a field attribute #[debug(units = "...")] on integer and float
+// fields, with the units:
+//
+//   - "bytes", printed with binary prefixes: `512 B`, `1.5 MiB`;
+//   - "duration_ms", for a number of milliseconds: `250ms`, `2.3s`;
+//   - "percent", for a number which is already a percentage: `42%`.
+//
+// Under `{:#?}` the raw value follows in parentheses, as in `1.5 MiB (1572864)`.
+//
+// A type parameter printed with units needs to be a number as well as Debug,
+// so the inferred bound is `T: Debug + Number` rather than just `T: Debug`.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Metrics {
+    #[debug(units = "bytes")]
+    heap: u64,
+    #[debug(units = "bytes")]
+    stack: usize,
+    #[debug(units = "duration_ms")]
+    uptime: u32,
+    #[debug(units = "duration_ms")]
+    latency: f64,
+    #[debug(units = "percent")]
+    cpu: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Blob<T> {
+    #[debug(units = "bytes")]
+    size: T,
+}
+
+fn main() {
+    let metrics = Metrics {
+        heap: 1572864,
+        stack: 512,
+        uptime: 2300,
+        latency: 12.5,
+        cpu: 42,
+    };
+
+    let debug = format!("{:?}", metrics);
+    let expected = concat!(
+        "Metrics { heap: 1.5 MiB, stack: 512 B, uptime: 2.3s, ",
+        "latency: 12.5ms, cpu: 42% }",
+    );
+    assert_eq!(debug, expected);
+
+    let pretty = format!("{:#?}", metrics);
+    let expected = r#"
+Metrics {
+    heap: 1.5 MiB (1572864),
+    stack: 512 B (512),
+    uptime: 2.3s (2300),
+    latency: 12.5ms (12.5),
+    cpu: 42% (42),
+}"#;
+    assert_eq!(pretty, &expected[1..]);
+
+    let blob = Blob { size: 2048u32 };
+    assert_eq!(format!("{:?}", blob), "Blob { size: 2 KiB }");
+}
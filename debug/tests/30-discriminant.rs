@@ -0,0 +1,101 @@
+// Dumps of a wire protocol need both the symbolic name of a C-like enum and
+// the value it is encoded as. Support an enum-level #[debug(discriminant)]
+// which prints each variant followed by its discriminant:
+//
+//     SMI = 2
+//
+// An optional radix of 2, 8 or 16 switches to a prefixed number in
+// parentheses, zero-padded to as many digits as the largest discriminant:
+//
+//     #[debug(discriminant(radix = 2))]  =>  SMI (0b010)
+//
+// Implicit discriminants count the same way they do in the language. Outside
+// of decimal a negative discriminant prints as it is stored, in two's
+// complement at the width of the enum's #[repr]: -1 in an `i8` is 0xff. With
+// an unsigned #[repr], decimal output is unsigned too, even for `u128::MAX`.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(discriminant(radix = 2))]
+pub enum DeliveryMode {
+    Fixed = 0b000,
+    Lowest = 0b001,
+    SMI = 0b010,
+    RemoteRead = 0b011,
+    NMI = 0b100,
+    Init = 0b101,
+    Startup = 0b110,
+    External = 0b111,
+}
+
+#[derive(CustomDebug)]
+#[debug(discriminant)]
+pub enum Level {
+    Low = 10,
+    Mid,
+    High = 100,
+}
+
+#[derive(CustomDebug)]
+#[debug(discriminant(radix = 16))]
+#[repr(u16)]
+pub enum Port {
+    Http = 80,
+    Https = 443,
+}
+
+#[derive(CustomDebug)]
+#[debug(discriminant(radix = 16))]
+#[repr(i8)]
+pub enum Delta {
+    Down = -1,
+    Up = 1,
+}
+
+#[derive(CustomDebug)]
+#[debug(discriminant)]
+#[repr(i8)]
+pub enum Sign {
+    Negative = -1,
+    Positive = 1,
+}
+
+#[derive(CustomDebug)]
+#[debug(discriminant)]
+#[repr(u128)]
+pub enum Wide {
+    Zero = 0,
+    Max = u128::MAX,
+}
+
+#[derive(CustomDebug)]
+pub struct Frame {
+    mode: DeliveryMode,
+    level: Level,
+}
+
+fn main() {
+    assert_eq!(format!("{:?}", DeliveryMode::SMI), "SMI (0b010)");
+    assert_eq!(format!("{:?}", DeliveryMode::External), "External (0b111)");
+    assert_eq!(format!("{:?}", Level::Mid), "Mid = 11");
+    assert_eq!(format!("{:?}", Port::Http), "Http (0x050)");
+    assert_eq!(format!("{:?}", Port::Https), "Https (0x1bb)");
+    assert_eq!(format!("{:?}", Delta::Down), "Down (0xff)");
+    assert_eq!(format!("{:?}", Delta::Up), "Up (0x01)");
+    assert_eq!(format!("{:?}", Sign::Negative), "Negative = -1");
+    assert_eq!(format!("{:?}", Wide::Zero), "Zero = 0");
+    assert_eq!(
+        format!("{:?}", Wide::Max),
+        "Max = 340282366920938463463374607431768211455",
+    );
+
+    let frame = Frame {
+        mode: DeliveryMode::NMI,
+        level: Level::High,
+    };
+    assert_eq!(
+        format!("{:?}", frame),
+        "Frame { mode: NMI (0b100), level: High = 100 }",
+    );
+}
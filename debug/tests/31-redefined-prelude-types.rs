@@ -0,0 +1,103 @@
+// Like 09-redefined-prelude-types in the builder project: does the generated
+// code still work if prelude names like Result, Option or Some, or the name
+// `fmt`, mean something different in the caller's code?
+//
+// Generated code should also work in a #![no_std] crate, where there is no
+// `::std` to refer to. Refer to everything through `::core` instead, and to
+// anything needing an allocator through the derive_debug crate.
+//
+// This test links std under a different name so that it can still run, while
+// `::std` stays unresolved just like in a no_std crate.
+
+#![no_std]
+
+extern crate std as realstd;
+
+use derive_debug::{CustomDebug, CustomDisplay, DebugDiff};
+use realstd::format;
+use realstd::string::ToString;
+
+type Option = ();
+type Some = ();
+type None = ();
+type Result = ();
+type Box = ();
+type Ok = ();
+type Err = ();
+type Vec = ();
+type String = ();
+type Debug = ();
+type Formatter = ();
+
+#[allow(dead_code)]
+mod fmt {}
+
+fn hex(value: &u8, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    write!(f, "{:#x}", value)
+}
+
+#[derive(CustomDebug, CustomDisplay)]
+#[debug(logfmt, max_depth = 4, type_params)]
+#[display("{name}")]
+pub struct Field<T> {
+    name: &'static str,
+    #[debug = "0b{:08b}"]
+    #[debug(alternate = "{}")]
+    bitmask: u8,
+    #[debug(with = "hex")]
+    mask: u8,
+    #[debug(redact(len))]
+    secret: &'static str,
+    #[debug(limit = 2)]
+    items: [T; 3],
+    #[debug(units = "bytes")]
+    size: u32,
+    #[debug(hex)]
+    raw: [u8; 2],
+    #[debug(flatten)]
+    inner: Inner,
+    #[debug(skip_if = "is_zero")]
+    zero: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Inner {
+    #[debug(fmt = "{}-{}", self.inner_a, 1)]
+    inner_a: u8,
+}
+
+fn is_zero(value: &u8) -> bool {
+    *value == 0
+}
+
+#[derive(CustomDebug)]
+#[debug(discriminant(radix = 2))]
+pub enum Mode {
+    A = 1,
+    B = 2,
+}
+
+fn main() {
+    let field = Field {
+        name: "F",
+        bitmask: 3,
+        mask: 255,
+        secret: "abc",
+        items: [1u8, 2, 3],
+        size: 2048,
+        raw: [0xab, 0xcd],
+        inner: Inner { inner_a: 7 },
+        zero: 0,
+    };
+    let debug = format!("{:?}", field);
+    let expected = concat!(
+        "Field<u8> { name: \"F\", bitmask: 0b00000011, mask: 0xff, ",
+        "secret: <redacted len=3>, items: [1, 2, ... 1 more], size: 2 KiB, ",
+        "raw: abcd, inner_a: 7-1 }",
+    );
+    assert_eq!(debug, expected);
+    assert_eq!(field.to_string(), "F");
+    assert_eq!(field.debug_diff(&field), "");
+    assert!(field.fmt_kv().to_string().starts_with("name=F bitmask=0b00000011"));
+    assert_eq!(format!("{:?}", Mode::B), "B (0b10)");
+}
@@ -0,0 +1,20 @@
+// An alternate format on a skipped or redacted field would print the hidden
+// value under `{:#?}`, so the combination is rejected.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Credentials {
+    user: &'static str,
+    #[debug(redact)]
+    #[debug(alternate = "{:?}")]
+    password: String,
+}
+
+#[derive(CustomDebug)]
+pub struct Token {
+    #[debug(skip, alternate = "{}")]
+    secret: String,
+}
+
+fn main() {}
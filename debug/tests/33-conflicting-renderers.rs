@@ -0,0 +1,61 @@
+// Only one attribute may decide how a field's value is printed. Combining, for
+// example, a format string with a `with` function would otherwise silently
+// ignore one of them. A flattened field prints through its own fields, so it
+// cannot have a renderer either, least of all `redact`.
+
+use derive_debug::CustomDebug;
+
+fn hex(value: &u8, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    write!(f, "{:#x}", value)
+}
+
+#[derive(CustomDebug)]
+pub struct Field {
+    #[debug = "{:x}"]
+    #[debug(with = "hex")]
+    bitmask: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Credentials {
+    #[debug(redact, limit = 4)]
+    password: String,
+}
+
+#[derive(CustomDebug)]
+pub struct Packet {
+    #[debug(hex, bytes)]
+    raw: Vec<u8>,
+}
+
+#[derive(CustomDebug)]
+pub struct Metrics {
+    #[debug(units = "bytes", units = "percent")]
+    size: u64,
+}
+
+#[derive(CustomDebug)]
+pub struct Conn {
+    host: &'static str,
+    #[debug(flatten, redact)]
+    login: Login,
+}
+
+#[derive(CustomDebug)]
+pub struct Session {
+    #[debug(with = "fmt_login")]
+    #[debug(flatten)]
+    login: Login,
+}
+
+#[derive(CustomDebug)]
+pub struct Login {
+    user: &'static str,
+    password: &'static str,
+}
+
+fn fmt_login(login: &Login, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    f.write_str(login.user)
+}
+
+fn main() {}
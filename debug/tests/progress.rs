@@ -1,12 +1,37 @@
 #[test]
 fn tests() {
     let t = trybuild::TestCases::new();
-    //t.pass("tests/01-parse.rs");
-    //t.pass("tests/02-impl-debug.rs");
-    //t.pass("tests/03-custom-format.rs");
-    //t.pass("tests/04-type-parameter.rs");
-    //t.pass("tests/05-phantom-data.rs");
-    //t.pass("tests/06-bound-trouble.rs");
-    //t.pass("tests/07-associated-type.rs");
-    //t.pass("tests/08-escape-hatch.rs");
+    t.pass("tests/01-parse.rs");
+    t.pass("tests/02-impl-debug.rs");
+    t.pass("tests/03-custom-format.rs");
+    t.pass("tests/04-type-parameter.rs");
+    t.pass("tests/05-phantom-data.rs");
+    t.pass("tests/06-bound-trouble.rs");
+    t.pass("tests/07-associated-type.rs");
+    t.pass("tests/08-escape-hatch.rs");
+    t.pass("tests/09-enum.rs");
+    t.pass("tests/10-tuple-struct.rs");
+    t.pass("tests/11-skip-redact.rs");
+    t.compile_fail("tests/12-unsupported-attribute.rs");
+    t.pass("tests/13-debug-with.rs");
+    t.pass("tests/14-fmt-args.rs");
+    t.compile_fail("tests/15-fmt-args-mismatch.rs");
+    t.compile_fail("tests/16-invalid-format.rs");
+    t.pass("tests/17-rename.rs");
+    t.pass("tests/18-limit.rs");
+    t.pass("tests/19-bytes.rs");
+    t.pass("tests/20-skip-if.rs");
+    t.pass("tests/21-flatten.rs");
+    t.pass("tests/22-alternate.rs");
+    t.pass("tests/23-field-bound.rs");
+    t.pass("tests/24-bound-shapes.rs");
+    t.pass("tests/25-max-depth.rs");
+    t.pass("tests/26-debug-diff.rs");
+    t.pass("tests/27-logfmt.rs");
+    t.pass("tests/28-display.rs");
+    t.pass("tests/29-units.rs");
+    t.pass("tests/30-discriminant.rs");
+    t.pass("tests/31-redefined-prelude-types.rs");
+    t.compile_fail("tests/32-alternate-hidden.rs");
+    t.compile_fail("tests/33-conflicting-renderers.rs");
 }
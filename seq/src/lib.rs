@@ -1,8 +1,208 @@
 use proc_macro::TokenStream;
+use proc_macro2::{Delimiter, Group, Literal, TokenStream as TokenStream2, TokenTree};
+use syn::parse::{Parse, ParseStream};
+use syn::{braced, parenthesized, parse_macro_input, Error, Ident, LitInt, Result, Token};
 
 #[proc_macro]
 pub fn seq(input: TokenStream) -> TokenStream {
-    let _ = input;
+    let input = parse_macro_input!(input as Seq);
+    input.expand().into()
+}
+
+/// `N in 0..8 { ... }`
+struct Seq {
+    var: Ident,
+    values: Vec<u64>,
+    body: TokenStream2,
+}
+
+impl Parse for Seq {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let var: Ident = input.parse()?;
+        input.parse::<Token![in]>()?;
+        let values = parse_range(input)?;
+        let content;
+        braced!(content in input);
+        let body: TokenStream2 = content.parse()?;
+        Ok(Seq { var, values, body })
+    }
+}
+
+/// Parses the values to iterate over, written as one of:
+///
+///   - `0..8` or `0..=7`;
+///   - `0..64 step 8`;
+///   - `(0..64)` followed by any number of `.step_by(8)` and `.rev()`, which
+///     apply in order the same way the iterator adapters do.
+fn parse_range(input: ParseStream) -> Result<Vec<u64>> {
+    if !input.peek(syn::token::Paren) {
+        let mut values = parse_bounds(input)?;
+        if input.peek(Ident) && input.fork().parse::<Ident>()? == "step" {
+            input.parse::<Ident>()?;
+            values = step_by(values, &input.parse()?)?;
+        }
+        return Ok(values);
+    }
+
+    let content;
+    parenthesized!(content in input);
+    let mut values = parse_bounds(&content)?;
+    if !content.is_empty() {
+        return Err(content.error("unexpected token after range"));
+    }
+    while input.peek(Token![.]) {
+        input.parse::<Token![.]>()?;
+        let method: Ident = input.parse()?;
+        let args;
+        parenthesized!(args in input);
+        if method == "rev" && args.is_empty() {
+            values.reverse();
+        } else if method == "step_by" {
+            values = step_by(values, &args.parse()?)?;
+            if !args.is_empty() {
+                return Err(args.error("expected a single step"));
+            }
+        } else {
+            return Err(Error::new(
+                method.span(),
+                "expected `.step_by(n)` or `.rev()`",
+            ));
+        }
+    }
+    Ok(values)
+}
+
+/// `lo..hi` or `lo..=hi`
+fn parse_bounds(input: ParseStream) -> Result<Vec<u64>> {
+    let lo: u64 = input.parse::<LitInt>()?.base10_parse()?;
+    let inclusive = if input.peek(Token![..=]) {
+        input.parse::<Token![..=]>()?;
+        true
+    } else {
+        input.parse::<Token![..]>()?;
+        false
+    };
+    let hi: u64 = input.parse::<LitInt>()?.base10_parse()?;
+    Ok(if inclusive {
+        (lo..=hi).collect()
+    } else {
+        (lo..hi).collect()
+    })
+}
+
+fn step_by(values: Vec<u64>, lit: &LitInt) -> Result<Vec<u64>> {
+    let step: usize = lit.base10_parse()?;
+    if step == 0 {
+        return Err(Error::new(lit.span(), "step must be greater than zero"));
+    }
+    Ok(values.into_iter().step_by(step).collect())
+}
+
+impl Seq {
+    /// Repeats only the `#(...)*` sections of the body if there are any, and
+    /// the whole body otherwise.
+    fn expand(&self) -> TokenStream2 {
+        if has_section(self.body.clone()) {
+            self.expand_sections(self.body.clone())
+        } else {
+            self.values
+                .iter()
+                .map(|&value| self.substitute(self.body.clone(), value))
+                .collect()
+        }
+    }
+
+    fn expand_sections(&self, stream: TokenStream2) -> TokenStream2 {
+        let tokens: Vec<TokenTree> = stream.into_iter().collect();
+        let mut output = TokenStream2::new();
+        let mut i = 0;
+        while i < tokens.len() {
+            if let Some(section) = section_at(&tokens[i..]) {
+                for &value in &self.values {
+                    output.extend(self.substitute(section.stream(), value));
+                }
+                i += 3;
+                continue;
+            }
+            match &tokens[i] {
+                TokenTree::Group(group) => {
+                    let stream = self.expand_sections(group.stream());
+                    output.extend(Some(TokenTree::Group(regroup(group, stream))));
+                }
+                token => output.extend(Some(token.clone())),
+            }
+            i += 1;
+        }
+        output
+    }
+
+    /// Replaces the loop variable by `value` as a literal, and pastes it onto
+    /// the end of identifiers written as `prefix#N`.
+    fn substitute(&self, stream: TokenStream2, value: u64) -> TokenStream2 {
+        let tokens: Vec<TokenTree> = stream.into_iter().collect();
+        let mut output = TokenStream2::new();
+        let mut i = 0;
+        while i < tokens.len() {
+            match &tokens[i] {
+                TokenTree::Ident(prefix) => {
+                    if let (Some(TokenTree::Punct(pound)), Some(TokenTree::Ident(var))) =
+                        (tokens.get(i + 1), tokens.get(i + 2))
+                    {
+                        if pound.as_char() == '#' && *var == self.var {
+                            let ident = Ident::new(&format!("{}{}", prefix, value), prefix.span());
+                            output.extend(Some(TokenTree::Ident(ident)));
+                            i += 3;
+                            continue;
+                        }
+                    }
+                    if *prefix == self.var {
+                        let mut literal = Literal::u64_unsuffixed(value);
+                        literal.set_span(prefix.span());
+                        output.extend(Some(TokenTree::Literal(literal)));
+                    } else {
+                        output.extend(Some(TokenTree::Ident(prefix.clone())));
+                    }
+                }
+                TokenTree::Group(group) => {
+                    let stream = self.substitute(group.stream(), value);
+                    output.extend(Some(TokenTree::Group(regroup(group, stream))));
+                }
+                token => output.extend(Some(token.clone())),
+            }
+            i += 1;
+        }
+        output
+    }
+}
+
+/// The group of a `#(...)*` section starting at the first token.
+fn section_at(tokens: &[TokenTree]) -> Option<&Group> {
+    match tokens {
+        [TokenTree::Punct(pound), TokenTree::Group(group), TokenTree::Punct(star), ..]
+            if pound.as_char() == '#'
+                && group.delimiter() == Delimiter::Parenthesis
+                && star.as_char() == '*' =>
+        {
+            Some(group)
+        }
+        _ => None,
+    }
+}
+
+fn has_section(stream: TokenStream2) -> bool {
+    let tokens: Vec<TokenTree> = stream.into_iter().collect();
+    (0..tokens.len()).any(|i| {
+        section_at(&tokens[i..]).is_some()
+            || match &tokens[i] {
+                TokenTree::Group(group) => has_section(group.stream()),
+                _ => false,
+            }
+    })
+}
 
-    unimplemented!()
+/// A group with the same delimiter and span as `group` around `stream`.
+fn regroup(group: &Group, stream: TokenStream2) -> Group {
+    let mut new = Group::new(group.delimiter(), stream);
+    new.set_span(group.span());
+    new
 }
@@ -0,0 +1,31 @@
+// Register banks are often laid out at a fixed stride. Support stepping
+// through the range, either written the way the iterator adapter would be or
+// with a `step` keyword:
+//
+//     seq!(N in (0..64).step_by(8) { ... })
+//     seq!(N in 0..64 step 8 { ... })
+//
+// Both mean N = 0, 8, 16, ..., 56.
+
+use seq::seq;
+
+seq!(N in (0..64).step_by(8) {
+    const OFFSETS: [usize; 8] = [#(N,)*];
+});
+
+seq!(N in 0..=32 step 16 {
+    #[derive(Debug, PartialEq)]
+    enum Register {
+        #(
+            Reg#N = N,
+        )*
+    }
+});
+
+fn main() {
+    assert_eq!(OFFSETS, [0, 8, 16, 24, 32, 40, 48, 56]);
+
+    assert_eq!(Register::Reg16 as u8, 16);
+    assert_eq!(Register::Reg32 as u8, 32);
+    assert_ne!(Register::Reg0, Register::Reg32);
+}
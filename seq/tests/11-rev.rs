@@ -0,0 +1,27 @@
+// Priority tables are easiest to write from the highest priority down. Support
+// reversing the range with `.rev()`, which can be combined with `.step_by(n)`
+// in either order, applying the same way as the iterator adapters would:
+//
+//     seq!(N in (0..8).rev() { ... })                // 7, 6, ..., 0
+//     seq!(N in (0..8).rev().step_by(3) { ... })     // 7, 4, 1
+//     seq!(N in (0..8).step_by(3).rev() { ... })     // 6, 3, 0
+
+use seq::seq;
+
+seq!(N in (0..8).rev() {
+    const PRIORITIES: [u8; 8] = [#(N,)*];
+});
+
+seq!(N in (0..8).rev().step_by(3) {
+    const REV_THEN_STEP: [u8; 3] = [#(N,)*];
+});
+
+seq!(N in (0..8).step_by(3).rev() {
+    const STEP_THEN_REV: [u8; 3] = [#(N,)*];
+});
+
+fn main() {
+    assert_eq!(PRIORITIES, [7, 6, 5, 4, 3, 2, 1, 0]);
+    assert_eq!(REV_THEN_STEP, [7, 4, 1]);
+    assert_eq!(STEP_THEN_REV, [6, 3, 0]);
+}